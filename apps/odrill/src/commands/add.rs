@@ -23,7 +23,7 @@ struct VersionDetail {
     url: String,
 }
 
-pub fn run(pkg_name: &str, _output: Option<&str>) -> Result<()> {
+pub fn run(pkg_name: &str) -> Result<()> {
     println!("🔍 Searching for package '{}'...", pkg_name);
 
     let registry_url =
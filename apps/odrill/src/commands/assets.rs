@@ -0,0 +1,73 @@
+//! odrill assets command - Diesel idstring tools
+
+use crate::config_global::GlobalConfig;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use diesel::{HashList, Idstring};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct AssetsArgs {
+    #[command(subcommand)]
+    pub command: AssetsCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AssetsCommands {
+    /// Print the idstring of asset paths or types
+    Hash {
+        /// Strings to hash (e.g. "guis/textures/pd2/hud_icons")
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+    /// Find the string behind an idstring in the hashlist
+    Lookup {
+        /// Idstring in hex (e.g. 5368e150b05a5b8c or @ID5368e150b05a5b8c@)
+        hex: String,
+
+        /// Hashlist file (defaults to `odrill config set hashlist <path>`)
+        #[arg(long)]
+        hashlist: Option<PathBuf>,
+    },
+}
+
+pub fn run(args: AssetsArgs) -> anyhow::Result<()> {
+    match args.command {
+        AssetsCommands::Hash { paths } => {
+            for path in paths {
+                println!("{}  {}", Idstring::new(&path), path);
+            }
+            Ok(())
+        }
+        AssetsCommands::Lookup { hex, hashlist } => lookup(&hex, hashlist),
+    }
+}
+
+fn lookup(hex: &str, hashlist: Option<PathBuf>) -> anyhow::Result<()> {
+    let id: Idstring = hex.parse()?;
+    let Some(path) = hashlist.or_else(configured_hashlist) else {
+        anyhow::bail!("No hashlist: pass --hashlist or run `odrill config set hashlist <path>`");
+    };
+    let list = HashList::read(&path)?;
+
+    // Bundle headers store idstrings little-endian; accept either byte order
+    if let Some(s) = list.get(id) {
+        println!("{}", s);
+    } else if let Some(s) = list.get(Idstring(id.0.swap_bytes())) {
+        println!("{} {}", s, "(byte-swapped)".dimmed());
+    } else {
+        anyhow::bail!(
+            "{} not found in {} ({} strings)",
+            id,
+            path.display(),
+            list.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Hashlist set in the global config, if any
+pub fn configured_hashlist() -> Option<PathBuf> {
+    GlobalConfig::load().ok()?.hashlist.map(PathBuf::from)
+}
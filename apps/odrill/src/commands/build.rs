@@ -1,32 +1,85 @@
 //! odrill build command - Bundle all hooks
 
+use clap::Args;
 use colored::Colorize;
-use compiler::{Compiler, superblt::generate_superblt_files};
+use compiler::passes::TagLogs;
+use compiler::{BuildEnv, Compiler};
 use pkg::OdrillProject;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Instant;
 
-pub fn run(force: bool, _watch: bool) -> anyhow::Result<()> {
+#[derive(Args, Default)]
+pub struct BuildArgs {
+    /// Clear the preprocessor and texture caches before building
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Watch for changes and rebuild
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// Build with the release profile (exposed as odrill_env.profile)
+    #[arg(long)]
+    pub release: bool,
+
+    /// Pin the build timestamp (SOURCE_DATE_EPOCH or last commit time)
+    #[arg(long)]
+    pub reproducible: bool,
+
+    /// Replace every translation with pseudo-localized text to spot hard-coded strings
+    #[arg(long)]
+    pub pseudo_loc: bool,
+
+    /// Local projects used instead of the installed packages of the same
+    /// name (set by `odrill run --with`)
+    #[arg(skip)]
+    pub packages: BTreeMap<String, PathBuf>,
+
+    /// Prefix `log()` output with the package name (set by `odrill run`)
+    #[arg(skip)]
+    pub tag_logs: bool,
+}
+
+pub fn run(args: BuildArgs) -> anyhow::Result<()> {
     let project_dir = std::env::current_dir()?;
     // Load project
     let project = OdrillProject::load(&project_dir)?;
 
-    // We clone project for the compiler, but we also nede it for superblt generation later.
-    // Compiler consumes it or takes reference?
-    // Compiler::new(project) takes ownership. OdrillProject is Clone?
-    // Project might be expensive to clone if it lists many files? No, just manifest and root.
-    // Assuming OdrillProject derives Clone. Check project.rs?
-
     println!("{}", "Building project...".cyan().bold());
     let start = Instant::now();
 
-    let mut compiler = Compiler::new(project.clone());
+    let profile = if args.release { "release" } else { "dev" };
+    let env = BuildEnv::detect(&project, profile, args.reproducible);
+    let mut compiler = Compiler::new(project.clone())
+        .with_env(env)
+        .with_hook_catalogue(super::hooks::catalogue());
+    if let Some(index) = super::hooks::class_index() {
+        compiler = compiler.with_class_index(index);
+    }
+    for (name, root) in &args.packages {
+        compiler = compiler.with_package(name, root);
+    }
+    if args.tag_logs {
+        compiler.add_pass(TagLogs::new(&project.manifest.package.name));
+    }
+    let target = compiler::output::for_project(&project);
 
-    if force {
+    if args.force {
+        compiler.clear_caches()?;
         println!("  {} cache", "clear".yellow());
     }
 
-    let results = compiler.compile_all()?;
-    let elapsed = start.elapsed();
+    target.validate(&compiler)?;
+    let results = if target.bundles_hooks() {
+        compiler.check_hook_ids()?;
+        let results = compiler.compile_all()?;
+        compiler.check_methods()?;
+        compiler.check_loc_keys()?;
+        results
+    } else {
+        Vec::new()
+    };
 
     let mut bundled = 0;
     let mut cached = 0;
@@ -51,30 +104,66 @@ pub fn run(force: bool, _watch: bool) -> anyhow::Result<()> {
         }
     }
 
-    // Generate SuperBLT files if target format is superblt
-    // Check manifest options. Assuming is_superblt() check was implicitly "if hooks > 0" or strictly structure.
-    // Payday 2 mods are usually SuperBLT.
-    // We can just always generate them or check a flag.
-    // For now, always generate if output mode is not specified or assumes SuperBLT.
-    // Or check manifest.options.
-
     let dist_dir = project_dir.join("dist");
 
-    // TODO: Add condition for superblt generation
+    // Libraries are only checked; they ship as sources
+    if !target.bundles_hooks() {
+        target.generate(&project, &dist_dir)?;
+        println!(
+            "  {} {} target, no mod files",
+            "check".green(),
+            target.name()
+        );
+        println!(
+            "\n{} in {:.2}s",
+            "Done!".green().bold(),
+            start.elapsed().as_secs_f64()
+        );
+        return Ok(());
+    }
+
     std::fs::create_dir_all(&dist_dir)?;
-    generate_superblt_files(&project.manifest, &dist_dir, &project_dir)?;
+    let overrides = compiler::overrides::copy(&project, &dist_dir)?;
+    let generated = target.generate(&project, &dist_dir)?;
+
+    for copied in overrides {
+        println!(
+            "  {} {}/{} ({} files)",
+            "copy".green(),
+            compiler::overrides::DIST_DIR,
+            copied.name,
+            copied.files
+        );
+    }
+
+    if !project.manifest.overrides.is_empty()
+        && let Some(path) = super::assets::configured_hashlist()
+    {
+        let hashlist = diesel::HashList::read(&path)?;
+        compiler::overrides::check_hashlist(&project, &hashlist);
+    }
 
     // Count loc files if any
     let loc_dir = project_dir.join("loc");
-    if loc_dir.exists() {
-        if let Ok(entries) = std::fs::read_dir(&loc_dir) {
-            let loc_count = entries.count();
-            if loc_count > 0 {
-                println!("  {} loc/ ({} files)", "copy".green(), loc_count);
-            }
+    if loc_dir.exists()
+        && let Ok(entries) = std::fs::read_dir(&loc_dir)
+    {
+        let loc_count = entries.count();
+        if loc_count > 0 {
+            println!("  {} loc/ ({} files)", "copy".green(), loc_count);
         }
     }
-    println!("  {} mod.txt, main.xml", "generate".green());
+    println!("  {} {}", "generate".green(), generated.join(", "));
+
+    if args.pseudo_loc {
+        let count = compiler::localization::write_pseudo(
+            &project.manifest.localization,
+            &project_dir,
+            &dist_dir,
+        )?;
+        println!("  {} {} loc files", "pseudo".yellow(), count);
+    }
+    let elapsed = start.elapsed();
 
     println!(
         "\n{} {} bundled, {} cached in {:.2}s",
@@ -0,0 +1,28 @@
+//! odrill check command - Validate the project without building
+
+use colored::Colorize;
+use compiler::Compiler;
+use pkg::OdrillProject;
+
+pub fn run() -> anyhow::Result<()> {
+    let project = OdrillProject::load(std::env::current_dir()?)?;
+    let mut compiler =
+        Compiler::new(project.clone()).with_hook_catalogue(super::hooks::catalogue());
+    if let Some(index) = super::hooks::class_index() {
+        compiler = compiler.with_class_index(index);
+    }
+    let target = compiler::output::for_project(&project);
+
+    println!("{}", "Checking project...".cyan().bold());
+
+    target.validate(&compiler)?;
+    if target.bundles_hooks() {
+        compiler.verify_all()?;
+        compiler.check_hook_ids()?;
+        compiler.check_methods()?;
+        compiler.check_loc_keys()?;
+    }
+
+    println!("{}", "No errors found.".green());
+    Ok(())
+}
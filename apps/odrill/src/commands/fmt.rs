@@ -21,7 +21,7 @@ pub fn run(args: FmtArgs) -> Result<()> {
         let entry = entry?;
         let path = entry.path();
 
-        if path.extension().map_or(false, |ext| ext == "lua") {
+        if path.extension().is_some_and(|ext| ext == "lua") {
             if path.to_string_lossy().contains("target") || path.to_string_lossy().contains(".git")
             {
                 continue;
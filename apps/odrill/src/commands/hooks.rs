@@ -0,0 +1,444 @@
+//! odrill hooks command - Hook scaffolding, game script catalogue and class index
+
+use crate::config_global::GlobalConfig;
+use anyhow::Context;
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use compiler::hooks::{ClassIndex, HookCatalogue, PARTIAL_NOTE};
+use compiler::parser::LuaParser;
+use pkg::OdrillProject;
+use std::path::{Path, PathBuf};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, value};
+
+#[derive(Args)]
+pub struct HooksArgs {
+    #[command(subcommand)]
+    pub command: HooksCommands,
+}
+
+#[derive(Subcommand)]
+pub enum HooksCommands {
+    /// Scaffold a hook: src/hooks/<id>/mod.lua and its [[hooks]] entry
+    Add {
+        /// Game script to hook (e.g. "lib/managers/hudmanagerpd2")
+        id: String,
+
+        #[arg(long, value_enum, default_value_t = AddKind::Post)]
+        kind: AddKind,
+
+        /// Class to hook (looked up in the class index when omitted)
+        #[arg(long)]
+        class: Option<String>,
+    },
+    /// List game scripts matching every word of a term
+    Search {
+        /// e.g. "hud teammate"
+        term: String,
+    },
+    /// Rebuild the catalogue from a decompiled game source tree
+    Refresh {
+        /// Directory containing the game's lib/ and core/ Lua sources
+        dir: PathBuf,
+    },
+    /// Index the classes and methods of a decompiled game source tree (also
+    /// refreshes the catalogue)
+    Index {
+        /// Directory containing the game's lib/ and core/ Lua sources
+        dir: PathBuf,
+    },
+    /// List the method signatures of a game class (needs `odrill hooks index`)
+    Show {
+        /// Class name (e.g. HUDManager)
+        class: String,
+    },
+}
+
+pub fn run(args: HooksArgs) -> anyhow::Result<()> {
+    match args.command {
+        HooksCommands::Add { id, kind, class } => add(&id, kind, class),
+        HooksCommands::Search { term } => search(&term),
+        HooksCommands::Refresh { dir } => refresh(dir),
+        HooksCommands::Index { dir } => {
+            refresh(dir.clone())?;
+            index(dir)
+        }
+        HooksCommands::Show { class } => show(&class),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum AddKind {
+    Post,
+    Pre,
+}
+
+fn add(id: &str, kind: AddKind, class: Option<String>) -> anyhow::Result<()> {
+    let root = std::env::current_dir()?;
+    let project = OdrillProject::load(&root)?;
+
+    let catalogue = catalogue();
+    if !catalogue.contains(id) {
+        match catalogue.suggest(id) {
+            Some(suggestion) => eprintln!(
+                "Warning: Unknown hook id '{}' (did you mean '{}'?)",
+                id, suggestion
+            ),
+            None => eprintln!("Warning: Unknown hook id '{}'", id),
+        }
+        if catalogue.is_partial() {
+            eprintln!("Note: {}", PARTIAL_NOTE);
+        }
+    }
+
+    let (entry, output) = hook_paths(id, kind)?;
+    if root.join(&entry).exists() {
+        anyhow::bail!("{} already exists", entry.display());
+    }
+
+    let class = class.or_else(|| hook_class(&class_index()?, id));
+    let prefix: String = project
+        .manifest
+        .package
+        .name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let source = hook_source(
+        id,
+        kind,
+        class.as_deref(),
+        &format!("{}_{}", prefix, id.replace('/', "_")),
+    );
+
+    // Edit odrill.toml with toml_edit so comments and layout survive
+    let manifest_path = root.join("odrill.toml");
+    let mut doc = std::fs::read_to_string(&manifest_path)?
+        .parse::<DocumentMut>()
+        .context("Failed to parse odrill.toml")?;
+    add_hook_entry(&mut doc, id, kind, &entry, &output)?;
+
+    let entry_path = root.join(&entry);
+    if let Some(parent) = entry_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&entry_path, source)?;
+    std::fs::write(&manifest_path, doc.to_string())?;
+
+    println!("  {} {}", "create".green(), entry.display());
+    println!("  {} [[hooks]] {}", "update".green(), id);
+    if class.is_none() {
+        println!(
+            "Replace `ClassName` in {} with the class defined by {}",
+            entry.display(),
+            id
+        );
+    }
+    Ok(())
+}
+
+/// Entry and output of a new hook, from the full game script path so hooks on
+/// same-named scripts in different folders don't collide. Pre hooks go under
+/// `pre/`, next to the post hook of the same script.
+fn hook_paths(id: &str, kind: AddKind) -> anyhow::Result<(PathBuf, PathBuf)> {
+    let id = Path::new(id);
+    if id.as_os_str().is_empty()
+        || !id
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)))
+    {
+        anyhow::bail!("Hook id '{}' is not a game script path", id.display());
+    }
+
+    let base = match kind {
+        AddKind::Post => id.to_path_buf(),
+        AddKind::Pre => Path::new("pre").join(id),
+    };
+    Ok((
+        Path::new("src/hooks").join(&base).join("mod.lua"),
+        Path::new("hooks").join(base.with_extension("lua")),
+    ))
+}
+
+/// Append a `[[hooks]]` entry. Several hooks may share a game script, but
+/// not a kind or an output file.
+fn add_hook_entry(
+    doc: &mut DocumentMut,
+    id: &str,
+    kind: AddKind,
+    entry: &Path,
+    output: &Path,
+) -> anyhow::Result<()> {
+    if !doc.contains_key("hooks") {
+        doc["hooks"] = Item::ArrayOfTables(ArrayOfTables::new());
+    }
+    let hooks = doc["hooks"]
+        .as_array_of_tables_mut()
+        .context("`hooks` in odrill.toml is not an array of tables")?;
+
+    let kind_name = match kind {
+        AddKind::Post => "post",
+        AddKind::Pre => "pre",
+    };
+    let duplicate = hooks.iter().any(|hook| {
+        hook.get("id").and_then(Item::as_str) == Some(id)
+            && hook.get("kind").and_then(Item::as_str).unwrap_or("post") == kind_name
+    });
+    if duplicate {
+        anyhow::bail!("odrill.toml already has a {} hook for {}", kind_name, id);
+    }
+
+    let output = output.to_string_lossy().replace('\\', "/");
+    let taken = hooks.iter().find(|hook| {
+        hook.get("output")
+            .and_then(Item::as_str)
+            .is_some_and(|o| o.replace('\\', "/") == output)
+    });
+    if let Some(hook) = taken {
+        anyhow::bail!(
+            "{} is already the output of the hook for {}",
+            output,
+            hook.get("id").and_then(Item::as_str).unwrap_or("?")
+        );
+    }
+
+    let mut table = Table::new();
+    if kind == AddKind::Pre {
+        table["kind"] = value("pre");
+    }
+    table["id"] = value(id);
+    table["entry"] = value(entry.to_string_lossy().replace('\\', "/"));
+    table["output"] = value(output);
+    hooks.push(table);
+    Ok(())
+}
+
+/// Class defined by a game script, from the class index
+fn hook_class(index: &ClassIndex, id: &str) -> Option<String> {
+    index.classes_in(id).first().map(|c| c.to_string())
+}
+
+/// Boilerplate hooking the class's `init`
+fn hook_source(id: &str, kind: AddKind, class: Option<&str>, hook_name: &str) -> String {
+    let function = match kind {
+        AddKind::Post => "PostHook",
+        AddKind::Pre => "PreHook",
+    };
+    let todo = match class {
+        Some(_) => String::new(),
+        None => format!(
+            "-- TODO: replace ClassName with the class defined by {}\n",
+            id
+        ),
+    };
+
+    format!(
+        "-- Hook for {id}\n\n{todo}Hooks:{function}({class}, \"init\", \"{hook_name}_init\", function(self, ...)\nend)\n",
+        class = class.unwrap_or("ClassName"),
+    )
+}
+
+fn search(term: &str) -> anyhow::Result<()> {
+    let catalogue = catalogue();
+    let matches = catalogue.search(term);
+
+    if matches.is_empty() {
+        match catalogue.suggest(term) {
+            Some(suggestion) => println!("No match. Did you mean {}?", suggestion.cyan()),
+            None => println!("No game script matches '{}'", term),
+        }
+        return Ok(());
+    }
+
+    for script in matches {
+        println!("{}", script);
+    }
+    Ok(())
+}
+
+fn refresh(dir: PathBuf) -> anyhow::Result<()> {
+    let catalogue = HookCatalogue::from_sources(&dir)?;
+    let path = catalogue_path()?;
+    catalogue.write(&path)?;
+
+    println!(
+        "{} {} game scripts to {}",
+        "Indexed".green(),
+        catalogue.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+fn index(dir: PathBuf) -> anyhow::Result<()> {
+    let index = ClassIndex::from_sources(&dir, &LuaParser::new("--"))?;
+    let path = class_index_path()?;
+    index.write(&path)?;
+
+    let methods: usize = index.classes.values().map(|c| c.methods.len()).sum();
+    println!(
+        "{} {} classes, {} methods to {}",
+        "Indexed".green(),
+        index.classes.len(),
+        methods,
+        path.display()
+    );
+    Ok(())
+}
+
+fn show(class: &str) -> anyhow::Result<()> {
+    let Some(index) = class_index() else {
+        anyhow::bail!("No class index: run `odrill hooks index <game sources>` first");
+    };
+    let Some(info) = index.class(class) else {
+        match index.suggest_class(class) {
+            Some(suggestion) => {
+                anyhow::bail!("Unknown class {} (did you mean {}?)", class, suggestion)
+            }
+            None => anyhow::bail!("Unknown class {}", class),
+        }
+    };
+
+    match &info.parent {
+        Some(parent) => println!(
+            "{} {}",
+            class.bold(),
+            format!("extends {}", parent).dimmed()
+        ),
+        None => println!("{}", class.bold()),
+    }
+    for (method, signature) in &info.methods {
+        println!(
+            "  {}  {}",
+            signature.display(class, method),
+            format!("{}.lua:{}", signature.script, signature.line).dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// The catalogue from `odrill hooks refresh`, else the bundled one
+pub fn catalogue() -> HookCatalogue {
+    catalogue_path()
+        .ok()
+        .filter(|path| path.is_file())
+        .and_then(|path| HookCatalogue::read(&path).ok())
+        .unwrap_or_else(HookCatalogue::bundled)
+}
+
+/// The class index from `odrill hooks index`, if any
+pub fn class_index() -> Option<ClassIndex> {
+    let path = class_index_path().ok()?;
+    path.is_file().then(|| ClassIndex::read(&path).ok())?
+}
+
+fn class_index_path() -> anyhow::Result<PathBuf> {
+    let config = GlobalConfig::get_path()?;
+    Ok(config.with_file_name("classes.json"))
+}
+
+fn catalogue_path() -> anyhow::Result<PathBuf> {
+    let config = GlobalConfig::get_path()?;
+    Ok(config.with_file_name("hooks.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compiler::hooks::{ClassInfo, MethodSignature};
+
+    #[test]
+    fn test_add_hook_entry() {
+        let mut doc = "# My mod\n[package]\nname = \"m\" # keep me\n\n[[hooks]]\nid = \"lib/managers/hudmanager\"\nentry = \"src/hud.lua\"\noutput = \"hooks/hudmanager.lua\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+
+        // Same game script, different output
+        add_hook_entry(
+            &mut doc,
+            "lib/managers/hudmanager",
+            AddKind::Pre,
+            Path::new("src/hooks/hud/mod.lua"),
+            Path::new("hooks/hud.lua"),
+        )
+        .unwrap();
+        let text = doc.to_string();
+        assert!(text.starts_with("# My mod\n[package]\nname = \"m\" # keep me\n"));
+        assert!(text.ends_with(
+            "[[hooks]]\nkind = \"pre\"\nid = \"lib/managers/hudmanager\"\nentry = \"src/hooks/hud/mod.lua\"\noutput = \"hooks/hud.lua\"\n"
+        ));
+
+        let taken = add_hook_entry(
+            &mut doc,
+            "lib/managers/hudmanagerpd2",
+            AddKind::Post,
+            Path::new("src/hooks/hudmanager/mod.lua"),
+            Path::new("hooks/hudmanager.lua"),
+        );
+        assert!(taken.is_err());
+
+        // Same game script and kind
+        let duplicate = add_hook_entry(
+            &mut doc,
+            "lib/managers/hudmanager",
+            AddKind::Post,
+            Path::new("src/hooks/other/mod.lua"),
+            Path::new("hooks/other.lua"),
+        );
+        assert!(duplicate.is_err());
+    }
+
+    #[test]
+    fn test_hook_paths() {
+        let (entry, output) = hook_paths("lib/units/x/foo", AddKind::Post).unwrap();
+        assert_eq!(entry, Path::new("src/hooks/lib/units/x/foo/mod.lua"));
+        assert_eq!(output, Path::new("hooks/lib/units/x/foo.lua"));
+
+        let (entry, output) = hook_paths("lib/managers/foo", AddKind::Pre).unwrap();
+        assert_eq!(entry, Path::new("src/hooks/pre/lib/managers/foo/mod.lua"));
+        assert_eq!(output, Path::new("hooks/pre/lib/managers/foo.lua"));
+
+        assert!(hook_paths("../foo", AddKind::Post).is_err());
+    }
+
+    #[test]
+    fn test_hook_source_pre() {
+        let source = hook_source(
+            "lib/managers/hudmanager",
+            AddKind::Pre,
+            Some("HUDManager"),
+            "m_hudmanager",
+        );
+        assert!(source.contains(
+            "Hooks:PreHook(HUDManager, \"init\", \"m_hudmanager_init\", function(self, ...)"
+        ));
+        assert!(!source.contains("TODO"));
+    }
+
+    #[test]
+    fn test_hook_class() {
+        let mut index = ClassIndex::default();
+        index.classes.insert(
+            "HUDManager".to_string(),
+            ClassInfo {
+                parent: None,
+                methods: [(
+                    "init".to_string(),
+                    MethodSignature {
+                        params: Vec::new(),
+                        is_static: false,
+                        script: "lib/managers/hudmanager".to_string(),
+                        line: 1,
+                    },
+                )]
+                .into(),
+            },
+        );
+
+        assert_eq!(
+            hook_class(&index, "lib/managers/hudmanager").as_deref(),
+            Some("HUDManager")
+        );
+        assert_eq!(hook_class(&index, "lib/managers/menumanager"), None);
+    }
+}
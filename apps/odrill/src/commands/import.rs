@@ -0,0 +1,216 @@
+//! odrill import - Turn an existing BLT/SuperBLT mod folder into a project
+
+use anyhow::Context;
+use clap::Args;
+use colored::Colorize;
+use compiler::import::ImportedMod;
+use pkg::manifest::{HookKind, OdrillManifest};
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, value};
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Mod folder containing mod.txt
+    pub path: PathBuf,
+
+    /// Project directory (defaults to the mod folder name)
+    pub dest: Option<PathBuf>,
+
+    /// Import into a non-empty directory
+    #[arg(long, short)]
+    pub force: bool,
+}
+
+pub fn run(args: ImportArgs) -> anyhow::Result<()> {
+    let import = ImportedMod::read(&args.path)?;
+
+    let dest = match args.dest {
+        Some(dest) => dest,
+        None => {
+            let name = args
+                .path
+                .canonicalize()?
+                .file_name()
+                .context("Mod folder has no name")?
+                .to_owned();
+            std::env::current_dir()?.join(name)
+        }
+    };
+    if dest.exists() && dest.read_dir()?.next().is_some() && !args.force {
+        anyhow::bail!(
+            "{} is not empty. Use --force to import anyway.",
+            dest.display()
+        );
+    }
+    if dest.canonicalize().ok() == args.path.canonicalize().ok() {
+        anyhow::bail!("Import into a different directory than the mod folder");
+    }
+
+    println!(
+        "📦 Importing '{}' into {}",
+        import.manifest.package.name,
+        dest.display()
+    );
+
+    std::fs::create_dir_all(&dest)?;
+    import.copy_files(&dest)?;
+    std::fs::write(dest.join("odrill.toml"), manifest_toml(&import.manifest))?;
+
+    let manifest = &import.manifest;
+    println!(
+        "  {} {} hooks, {} keybinds, {} assets, {} files",
+        "import".green(),
+        manifest.hooks.len(),
+        manifest.keybinds.len(),
+        manifest.assets.len(),
+        import.files.len()
+    );
+
+    if !import.notes.is_empty() {
+        println!("\n{}", "Check manually:".yellow().bold());
+        for note in &import.notes {
+            println!("  {} {}", "-".yellow(), note);
+        }
+    }
+
+    println!("\n{}", "Project imported!".green().bold());
+    println!("cd {}\nodrill build", dest.display());
+    Ok(())
+}
+
+/// odrill.toml for an imported mod, leaving out defaults
+fn manifest_toml(manifest: &OdrillManifest) -> String {
+    let mut doc = DocumentMut::new();
+    let pkg = &manifest.package;
+
+    let mut package = Table::new();
+    package["name"] = value(&pkg.name);
+    package["version"] = value(&pkg.version);
+    if let Some(description) = &pkg.description {
+        package["description"] = value(description);
+    }
+    package["authors"] = value(pkg.authors.iter().collect::<Array>());
+    doc["package"] = Item::Table(package);
+
+    if !manifest.assets.is_empty() {
+        doc["assets"] = value(
+            manifest
+                .assets
+                .iter()
+                .map(|a| path(&a.entry().from))
+                .collect::<Array>(),
+        );
+    }
+
+    let blt = &manifest.superblt;
+    let mut superblt = Table::new();
+    if let Some(contact) = &blt.contact {
+        superblt["contact"] = value(contact);
+    }
+    if let Some(color) = &blt.color {
+        superblt["color"] = value(color);
+    }
+    if let Some(image) = &blt.image {
+        superblt["image"] = value(path(image));
+    }
+    if let Some(blt_version) = blt.blt_version {
+        superblt["blt_version"] = value(blt_version as i64);
+    }
+    if blt.priority != 0 {
+        superblt["priority"] = value(blt.priority as i64);
+    }
+    let mut updates = ArrayOfTables::new();
+    for update in &blt.updates {
+        let mut table = Table::new();
+        table["identifier"] = value(&update.identifier);
+        for (key, field) in [
+            ("host", &update.host),
+            ("display_name", &update.display_name),
+            ("install_dir", &update.install_dir),
+        ] {
+            if let Some(field) = field {
+                table[key] = value(field);
+            }
+        }
+        updates.push(table);
+    }
+    if !updates.is_empty() {
+        superblt["updates"] = Item::ArrayOfTables(updates);
+    }
+    if !superblt.is_empty() {
+        doc["superblt"] = Item::Table(superblt);
+    }
+
+    let mut hooks = ArrayOfTables::new();
+    for hook in &manifest.hooks {
+        let mut table = Table::new();
+        match hook.kind {
+            HookKind::Post => {}
+            HookKind::Pre => table["kind"] = value("pre"),
+            HookKind::Persist => table["kind"] = value("persist"),
+            HookKind::Wildcard => table["kind"] = value("wildcard"),
+        }
+        if !hook.id.is_empty() {
+            table["id"] = value(&hook.id);
+        }
+        if let Some(global) = &hook.global {
+            table["global"] = value(global);
+        }
+        table["entry"] = value(path(&hook.entry));
+        table["output"] = value(path(&hook.output));
+        hooks.push(table);
+    }
+    if !hooks.is_empty() {
+        doc["hooks"] = Item::ArrayOfTables(hooks);
+    }
+
+    let mut keybinds = ArrayOfTables::new();
+    for keybind in &manifest.keybinds {
+        let mut table = Table::new();
+        table["id"] = value(&keybind.id);
+        table["name"] = value(&keybind.name);
+        if !keybind.description.is_empty() {
+            table["description"] = value(&keybind.description);
+        }
+        table["entry"] = value(path(&keybind.entry));
+        table["output"] = value(path(&keybind.output));
+        if keybind.run_in_menu {
+            table["run_in_menu"] = value(true);
+        }
+        if !keybind.run_in_game {
+            table["run_in_game"] = value(false);
+        }
+        if !keybind.localized {
+            table["localized"] = value(false);
+        }
+        keybinds.push(table);
+    }
+    if !keybinds.is_empty() {
+        doc["keybinds"] = Item::ArrayOfTables(keybinds);
+    }
+
+    let mut localization = ArrayOfTables::new();
+    for loc in &manifest.localization {
+        let mut table = Table::new();
+        table["directory"] = value(&loc.directory);
+        table["default"] = value(&loc.default);
+        if !loc.languages.is_empty() {
+            let mut languages = InlineTable::new();
+            for (language, file) in &loc.languages {
+                languages.insert(language, file.into());
+            }
+            table["languages"] = value(languages);
+        }
+        localization.push(table);
+    }
+    if !localization.is_empty() {
+        doc["localization"] = Item::ArrayOfTables(localization);
+    }
+
+    doc.to_string()
+}
+
+/// Manifest paths always use `/`
+fn path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
@@ -72,15 +72,14 @@ pub fn run() -> Result<()> {
             Ok(())
         };
 
-        if dest.exists() {
-            if let Some(locked) = lockfile.get(&name) {
-                if locked.version == version {
-                    println!("  {} {}@{} (cached)", "skip".yellow(), name, version);
-                    // Even if cached, we must scan its dependencies!
-                    process_params_dependencies(&dest)?;
-                    continue;
-                }
-            }
+        if dest.exists()
+            && let Some(locked) = lockfile.get(&name)
+            && locked.version == version
+        {
+            println!("  {} {}@{} (cached)", "skip".yellow(), name, version);
+            // Even if cached, we must scan its dependencies!
+            process_params_dependencies(&dest)?;
+            continue;
         }
 
         println!("  {} {}@{}", "fetch".blue(), name, version);
@@ -105,10 +104,10 @@ pub fn run() -> Result<()> {
         let checksum = compute_checksum(&data);
 
         // Verify against lockfile if exists
-        if let Some(locked) = lockfile.get(&name) {
-            if locked.checksum != checksum {
-                println!("  {} {} checksum mismatch!", "warn".yellow(), name);
-            }
+        if let Some(locked) = lockfile.get(&name)
+            && locked.checksum != checksum
+        {
+            println!("  {} {} checksum mismatch!", "warn".yellow(), name);
         }
 
         // Decode package
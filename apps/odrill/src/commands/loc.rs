@@ -0,0 +1,74 @@
+//! odrill loc command - Localization helpers
+
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use compiler::Compiler;
+use compiler::localization;
+use pkg::OdrillProject;
+use std::collections::BTreeSet;
+
+#[derive(Args)]
+pub struct LocArgs {
+    #[command(subcommand)]
+    pub command: LocCommands,
+}
+
+#[derive(Subcommand)]
+pub enum LocCommands {
+    /// Add keys used in sources and menus to the default language file
+    Extract {
+        /// Only list the missing keys
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn run(args: LocArgs) -> anyhow::Result<()> {
+    match args.command {
+        LocCommands::Extract { dry_run } => extract(dry_run),
+    }
+}
+
+fn extract(dry_run: bool) -> anyhow::Result<()> {
+    let project = OdrillProject::load(std::env::current_dir()?)?;
+    let Some(config) = project.manifest.localization.first() else {
+        anyhow::bail!("No [[localization]] in odrill.toml");
+    };
+    let default = project.root.join(&config.directory).join(&config.default);
+
+    let compiler = Compiler::new(project.clone());
+    let missing: BTreeSet<String> = compiler
+        .unknown_loc_keys()?
+        .into_iter()
+        .map(|r| r.key)
+        .collect();
+
+    if missing.is_empty() {
+        println!("{} No missing localization keys", "✓".green());
+        return Ok(());
+    }
+
+    for key in &missing {
+        println!("  {} {}", "add".green(), key);
+    }
+
+    if dry_run {
+        println!(
+            "\n{} keys missing from {}",
+            missing.len(),
+            default.display()
+        );
+        return Ok(());
+    }
+
+    let keys: Vec<String> = missing.into_iter().collect();
+    localization::append_keys(&default, &keys)?;
+    println!(
+        "\n{} Added {} keys to {}",
+        "Done!".green().bold(),
+        keys.len(),
+        default.display()
+    );
+
+    Ok(())
+}
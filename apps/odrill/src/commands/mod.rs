@@ -1,11 +1,16 @@
 //! CLI commands
 
 pub mod add;
+pub mod assets;
 pub mod build;
+pub mod check;
 pub mod clean;
 pub mod config;
 pub mod fmt;
+pub mod hooks;
+pub mod import;
 pub mod install;
+pub mod loc;
 pub mod login;
 pub mod new; // [NEW]
 pub mod publish;
@@ -0,0 +1,91 @@
+-- odrill hot reload runtime, installed by `odrill run --hot` for the session only
+--
+-- reload.txt is rewritten by odrill after each rebuild: a generation number,
+-- then one `<generation>\t<hook id>\t<mod path>\t<script>` line per reloaded
+-- hook. Scripts newer than the last generation seen are run again.
+
+if rawget(_G, "OdrillHot") then
+    return
+end
+
+local OdrillHot = {
+    marker = ModPath .. "reload.txt",
+    generation = 0,
+    next_check = 0,
+}
+rawset(_G, "OdrillHot", OdrillHot)
+
+-- Hooks ignores a hook id registered twice: drop the previous registration
+-- so the re-run script replaces it
+local function rehook(run)
+    local post_hook, pre_hook, add = Hooks.PostHook, Hooks.PreHook, Hooks.Add
+
+    Hooks.PostHook = function(self, object, func, id, fn)
+        self:RemovePostHook(id)
+        return post_hook(self, object, func, id, fn)
+    end
+    Hooks.PreHook = function(self, object, func, id, fn)
+        self:RemovePreHook(id)
+        return pre_hook(self, object, func, id, fn)
+    end
+    Hooks.Add = function(self, key, id, fn)
+        self:Remove(id)
+        return add(self, key, id, fn)
+    end
+
+    local ok, err = pcall(run)
+    Hooks.PostHook, Hooks.PreHook, Hooks.Add = post_hook, pre_hook, add
+    return ok, err
+end
+
+function OdrillHot:reload(hook_id, mod_path, script)
+    local required_script, mod_path_before = RequiredScript, ModPath
+    RequiredScript, ModPath = hook_id, mod_path
+
+    local ok, err = rehook(function()
+        dofile(mod_path .. script)
+    end)
+
+    RequiredScript, ModPath = required_script, mod_path_before
+
+    if ok then
+        log("[odrill] Reloaded " .. mod_path .. script)
+    else
+        log("[odrill] Reload of " .. mod_path .. script .. " failed: " .. tostring(err))
+    end
+end
+
+function OdrillHot:check(t)
+    if t < self.next_check then
+        return
+    end
+    self.next_check = t + 0.5
+
+    local file = io.open(self.marker, "r")
+    if not file then
+        return
+    end
+
+    local generation = tonumber(file:read("*l")) or 0
+    if generation <= self.generation then
+        file:close()
+        return
+    end
+
+    local seen = self.generation
+    self.generation = generation
+    for line in file:lines() do
+        local gen, hook_id, mod_path, script = line:match("^(%d+)\t([^\t]*)\t([^\t]*)\t(.+)$")
+        if gen and tonumber(gen) > seen then
+            self:reload(hook_id, mod_path, script)
+        end
+    end
+    file:close()
+end
+
+local function update(t)
+    OdrillHot:check(t)
+end
+
+Hooks:Add("MenuUpdate", "odrill_hot_menu_update", update)
+Hooks:Add("GameSetupUpdate", "odrill_hot_game_update", update)
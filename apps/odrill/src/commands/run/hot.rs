@@ -0,0 +1,329 @@
+//! `odrill run --hot`: a companion runtime mod re-running changed hooks in the
+//! game, fed by a thread rebuilding them as sources change
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use compiler::passes::TagLogs;
+use compiler::{BuildEnv, Compiler};
+use pkg::OdrillProject;
+use pkg::manifest::HookKind;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Folder of the runtime mod in mods/
+pub const HOT_MOD: &str = "odrill-hot";
+const RUNTIME: &str = include_str!("hot.lua");
+const MARKER: &str = "reload.txt";
+
+/// The installed runtime mod, removed when dropped
+pub struct HotReload {
+    mod_dir: PathBuf,
+}
+
+impl HotReload {
+    pub fn install(mods_dir: &Path) -> Result<Self> {
+        let mod_dir = mods_dir.join(HOT_MOD);
+        fs::create_dir_all(&mod_dir)?;
+
+        let mod_txt = serde_json::json!({
+            "name": "odrill hot reload",
+            "description": "Re-runs hooks rebuilt by odrill run --hot",
+            "version": env!("CARGO_PKG_VERSION"),
+            "hooks": [{ "hook_id": "lib/managers/menumanager", "script_path": "hot.lua" }],
+        });
+        fs::write(
+            mod_dir.join("mod.txt"),
+            serde_json::to_string_pretty(&mod_txt)?,
+        )?;
+        fs::write(mod_dir.join("hot.lua"), RUNTIME)?;
+        fs::write(mod_dir.join(MARKER), "0\n")?;
+
+        println!("🔥 Installed hot reload runtime to {}", mod_dir.display());
+        Ok(Self { mod_dir })
+    }
+
+    /// Watch the projects' sources in a background thread
+    pub fn watch(
+        &self,
+        projects: &[OdrillProject],
+        packages: &BTreeMap<String, PathBuf>,
+        mods_dir: &Path,
+    ) -> Result<()> {
+        let mut watched = Vec::new();
+        for project in projects {
+            watched.push(WatchedProject::new(project.clone(), packages, mods_dir)?);
+        }
+        let mut marker = Marker {
+            path: self.mod_dir.join(MARKER),
+            generation: 0,
+            scripts: BTreeMap::new(),
+        };
+
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(Duration::from_millis(500));
+                for project in &mut watched {
+                    project.poll(&mut marker);
+                }
+            }
+        });
+
+        println!("🔥 Watching the hooks' sources for hot reload");
+        Ok(())
+    }
+}
+
+impl Drop for HotReload {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.mod_dir);
+    }
+}
+
+/// reload.txt: every reloaded script with the generation it was last rebuilt in
+struct Marker {
+    path: PathBuf,
+    generation: u64,
+    /// (mod path, script) → (generation, hook id)
+    scripts: BTreeMap<(String, String), (u64, String)>,
+}
+
+impl Marker {
+    fn touch(&mut self, reloaded: Vec<(String, String, String)>) -> Result<()> {
+        self.generation += 1;
+        for (hook_id, mod_path, script) in reloaded {
+            self.scripts
+                .insert((mod_path, script), (self.generation, hook_id));
+        }
+
+        let mut content = format!("{}\n", self.generation);
+        for ((mod_path, script), (generation, hook_id)) in &self.scripts {
+            content.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                generation, hook_id, mod_path, script
+            ));
+        }
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// A bundled script and the files it's built from
+struct Script {
+    /// Hook kind, `None` for keybind handlers
+    kind: Option<HookKind>,
+    id: String,
+    entry: PathBuf,
+    output: PathBuf,
+    dependencies: Vec<PathBuf>,
+}
+
+struct WatchedProject {
+    project: OdrillProject,
+    /// Detected once, so rebuilds match the initial build without running git
+    env: BuildEnv,
+    /// The session's libraries, as passed to the initial build
+    packages: BTreeMap<String, PathBuf>,
+    /// Canonical project root, as the compiler reports dependencies
+    root: PathBuf,
+    install_dir: PathBuf,
+    scripts: Vec<Script>,
+    stamps: HashMap<PathBuf, SystemTime>,
+}
+
+impl WatchedProject {
+    fn new(
+        project: OdrillProject,
+        packages: &BTreeMap<String, PathBuf>,
+        mods_dir: &Path,
+    ) -> Result<Self> {
+        let name = &project.manifest.package.name;
+        let install_dir = mods_dir.join(name);
+        let env = BuildEnv::detect(&project, "dev", false);
+        let packages: BTreeMap<String, PathBuf> = packages
+            .iter()
+            .filter(|(package, _)| *package != name)
+            .map(|(package, root)| (package.clone(), root.clone()))
+            .collect();
+        let compiler = compiler(&project, &env, &packages);
+
+        let hooks = project.manifest.hooks.iter().map(|h| {
+            (
+                Some(h.kind),
+                h.id.clone(),
+                h.entry.clone(),
+                h.output.clone(),
+            )
+        });
+        let keybinds = project
+            .manifest
+            .keybinds
+            .iter()
+            .map(|k| (None, k.id.clone(), k.entry.clone(), k.output.clone()));
+
+        let mut scripts = Vec::new();
+        for (kind, id, entry, output) in hooks.chain(keybinds) {
+            scripts.push(Script {
+                dependencies: compiler.entry_dependencies(&entry)?,
+                kind,
+                id,
+                entry,
+                output,
+            });
+        }
+
+        let mut watched = Self {
+            root: project.root.canonicalize()?,
+            project,
+            env,
+            packages,
+            install_dir,
+            scripts,
+            stamps: HashMap::new(),
+        };
+        watched.stamps = watched.stamps();
+        Ok(watched)
+    }
+
+    /// Modification times of odrill.toml and of every file a script is built
+    /// from, embeds and `use`d modules included
+    fn stamps(&self) -> HashMap<PathBuf, SystemTime> {
+        let manifest = std::iter::once(self.root.join("odrill.toml"));
+        let dependencies = self
+            .scripts
+            .iter()
+            .flat_map(|s| s.dependencies.iter().cloned());
+
+        manifest
+            .chain(dependencies)
+            .filter_map(|path| {
+                let time = modified(&path)?;
+                Some((path, time))
+            })
+            .collect()
+    }
+
+    fn poll(&mut self, marker: &mut Marker) {
+        let stamps = self.stamps();
+        let mut changed: Vec<PathBuf> = stamps
+            .iter()
+            .filter(|(path, time)| self.stamps.get(*path) != Some(*time))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.stamps
+                    .keys()
+                    .filter(|path| !stamps.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        self.stamps = stamps;
+        if changed.is_empty() {
+            return;
+        }
+
+        let name = self.project.manifest.package.name.clone();
+        let manifest = self.root.join("odrill.toml");
+        if let Some(i) = changed.iter().position(|p| *p == manifest) {
+            changed.remove(i);
+            println!(
+                "{}",
+                format!(
+                    "🔥 [{}] odrill.toml changed: restart the game to apply it",
+                    name
+                )
+                .yellow()
+            );
+        }
+
+        let mut compiler = compiler(&self.project, &self.env, &self.packages);
+        let mut reloaded = Vec::new();
+        for script in &mut self.scripts {
+            if !script.dependencies.iter().any(|d| changed.contains(d)) {
+                continue;
+            }
+
+            let result = match compiler.compile_entry(&script.entry, &script.output) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!(
+                        "{}",
+                        format!("🔥 [{}] {} failed: {}", name, script.entry.display(), e).red()
+                    );
+                    continue;
+                }
+            };
+            script.dependencies = result.source_files;
+
+            let output = script.output.to_string_lossy().replace('\\', "/");
+            let dest = self.install_dir.join(&script.output);
+            if let Err(e) = fs::copy(&result.output_path, &dest) {
+                eprintln!("🔥 [{}] Failed to install {}: {}", name, output, e);
+                continue;
+            }
+
+            match script.kind {
+                Some(HookKind::Post) => {
+                    println!("🔥 [{}] Reloading {}", name, output.green());
+                    reloaded.push((script.id.clone(), format!("mods/{}/", name), output));
+                }
+                Some(kind) => println!(
+                    "{}",
+                    format!(
+                        "🔥 [{}] Rebuilt {}: {} hooks need a game restart",
+                        name,
+                        output,
+                        kind_name(kind)
+                    )
+                    .yellow()
+                ),
+                None => println!(
+                    "🔥 [{}] Rebuilt {} (used on the next key press)",
+                    name,
+                    output.green()
+                ),
+            }
+        }
+
+        // Start watching files the rebuilt scripts now depend on
+        for path in self.scripts.iter().flat_map(|s| &s.dependencies) {
+            if !self.stamps.contains_key(path)
+                && let Some(time) = modified(path)
+            {
+                self.stamps.insert(path.clone(), time);
+            }
+        }
+
+        if !reloaded.is_empty()
+            && let Err(e) = marker.touch(reloaded)
+        {
+            eprintln!("🔥 {}", e);
+        }
+    }
+}
+
+fn compiler(
+    project: &OdrillProject,
+    env: &BuildEnv,
+    packages: &BTreeMap<String, PathBuf>,
+) -> Compiler {
+    packages.iter().fold(
+        Compiler::new(project.clone())
+            .with_env(env.clone())
+            .with_pass(TagLogs::new(&project.manifest.package.name)),
+        |compiler, (name, root)| compiler.with_package(name, root),
+    )
+}
+
+fn kind_name(kind: HookKind) -> &'static str {
+    match kind {
+        HookKind::Post => "post",
+        HookKind::Pre => "pre",
+        HookKind::Persist => "persist",
+        HookKind::Wildcard => "wildcard",
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
@@ -1,71 +1,237 @@
 //! Mod isolation system with backup and restore
+//!
+//! Every directory moved aside, and everything installed next to the user's
+//! mods without isolation, is recorded in a journal in the game dir first,
+//! so a session killed before it could clean up is undone by
+//! `odrill run --restore`.
 
-use crate::constants::BACKUP_PREFIX;
-use anyhow::{Context, Result, anyhow};
+use crate::constants::{BACKUP_PREFIX, ISOLATION_JOURNAL};
+use anyhow::{Context, Result, anyhow, bail};
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-/// RAII guard that automatically restores backed-up directories on drop
+/// Serializes restores between the main thread and the signal handler
+static RESTORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// On-disk record of an isolation session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Journal {
+    pub pid: u32,
+    pub started: String,
+    pub moved: Vec<MovedDir>,
+    /// Installed into the real game dirs, removed on restore
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub installed: Vec<PathBuf>,
+}
+
+/// A directory renamed to its `~` backup
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovedDir {
+    pub dir: PathBuf,
+    pub backup: PathBuf,
+}
+
+impl Journal {
+    pub fn path(game_path: &Path) -> PathBuf {
+        game_path.join(ISOLATION_JOURNAL)
+    }
+
+    pub fn read(game_path: &Path) -> Result<Option<Self>> {
+        let path = Self::path(game_path);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let journal = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(journal))
+    }
+
+    fn write(&self, game_path: &Path) -> Result<()> {
+        let path = Self::path(game_path);
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// RAII guard that restores the backed-up directories on drop, and on
+/// SIGINT / SIGTERM / terminal close
 pub struct IsolationGuard {
-    pub mods_dir: PathBuf,
-    pub overrides_dir: PathBuf,
+    game_path: PathBuf,
+}
+
+impl IsolationGuard {
+    /// Move `dirs` aside to their `~` backups, journaling them first.
+    ///
+    /// Refuses to start when a previous session left a journal or a backup
+    /// behind, rather than guessing which copy is the user's.
+    pub fn acquire(game_path: &Path, dirs: &[PathBuf]) -> Result<Self> {
+        if let Some(journal) = Journal::read(game_path)? {
+            bail!(
+                "An odrill run session (pid {}, started {}) did not restore the game directory.\n\
+                 If it's no longer running, run: odrill run --restore",
+                journal.pid,
+                journal.started
+            );
+        }
+        for dir in dirs {
+            let backup = backup_path(dir)?;
+            if backup.exists() {
+                bail!(
+                    "Backup {} already exists.\nRun `odrill run --restore` or move it out of the way first.",
+                    backup.display()
+                );
+            }
+        }
+
+        let mut journal = Journal {
+            pid: std::process::id(),
+            started: chrono::Local::now().to_rfc3339(),
+            moved: Vec::new(),
+            installed: Vec::new(),
+        };
+        for dir in dirs.iter().filter(|d| d.exists()) {
+            journal.moved.push(MovedDir {
+                dir: dir.clone(),
+                backup: backup_path(dir)?,
+            });
+        }
+        journal.write(game_path)?;
+
+        let guard = Self {
+            game_path: game_path.to_path_buf(),
+        };
+        guard.set_signal_handler();
+
+        // Dropping the guard on error restores whatever was already moved
+        for moved in &journal.moved {
+            backup_dir(&moved.dir, &moved.backup)?;
+        }
+
+        Ok(guard)
+    }
+
+    /// Journal a file or directory about to be installed outside the moved
+    /// directories, so the restore removes it
+    pub fn record_install(&self, path: &Path) -> Result<()> {
+        let _lock = RESTORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut journal = Journal::read(&self.game_path)?
+            .ok_or_else(|| anyhow!("The isolation journal disappeared"))?;
+        journal.installed.push(path.to_path_buf());
+        journal.write(&self.game_path)
+    }
+
+    fn set_signal_handler(&self) {
+        let game_path = self.game_path.clone();
+        let result = ctrlc::set_handler(move || {
+            println!("\n🛑 Interrupted, restoring environment...");
+            if let Err(e) = restore(&game_path) {
+                eprintln!("{}", format!("Failed to restore: {}", e).red());
+                eprintln!("Run `odrill run --restore` once the game is closed.");
+            }
+            std::process::exit(130);
+        });
+        if let Err(e) = result {
+            eprintln!("Warning: Couldn't install the Ctrl-C handler: {}", e);
+        }
+    }
 }
 
 impl Drop for IsolationGuard {
     fn drop(&mut self) {
         println!("\n🧹 Restoring environment...");
 
-        if let Err(e) = restore_dir(&self.mods_dir) {
-            eprintln!("Failed to restore mods: {}", e);
+        if let Err(e) = restore(&self.game_path) {
+            eprintln!("Failed to restore: {}", e);
+            eprintln!("Run `odrill run --restore` once the game is closed.");
         }
+    }
+}
+
+/// Undo the session recorded in the journal. The journal is only removed
+/// once every directory is back. Returns false if there was nothing to undo.
+pub fn restore(game_path: &Path) -> Result<bool> {
+    let _lock = RESTORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
 
-        if let Err(e) = restore_dir(&self.overrides_dir) {
-            eprintln!("Failed to restore mod_overrides: {}", e);
+    let Some(journal) = Journal::read(game_path)? else {
+        return Ok(false);
+    };
+
+    let mut failed = Vec::new();
+    for path in journal.installed.iter().rev() {
+        if let Err(e) = remove_installed(path) {
+            failed.push(format!("{}: {}", path.display(), e));
+        }
+    }
+    for moved in journal.moved.iter().rev() {
+        if let Err(e) = restore_dir(&moved.dir, &moved.backup) {
+            failed.push(format!("{}: {}", moved.dir.display(), e));
         }
     }
+    if !failed.is_empty() {
+        bail!("{}", failed.join("\n"));
+    }
+
+    fs::remove_file(Journal::path(game_path))?;
+    Ok(true)
 }
 
-/// Backup a directory by renaming it with a ~ prefix
-///
-/// Returns the path to the backup directory
-pub fn backup_dir(dir: &Path) -> Result<PathBuf> {
-    if !dir.exists() {
-        return Ok(dir.to_path_buf());
+/// Recover from an interrupted session. Without a journal, `~` backups are
+/// only put back where their directory is missing; when both exist it's
+/// unclear which one is the dev copy, so neither is touched.
+pub fn restore_interrupted(game_path: &Path, dirs: &[PathBuf]) -> Result<()> {
+    if restore(game_path)? {
+        return Ok(());
+    }
+
+    let mut ambiguous = Vec::new();
+    for dir in dirs {
+        let backup = backup_path(dir)?;
+        if !backup.exists() {
+            continue;
+        }
+        if dir.exists() {
+            ambiguous.push(format!("{} and {}", dir.display(), backup.display()));
+            continue;
+        }
+        fs::rename(&backup, dir)?;
+        print_move(&backup, dir);
     }
 
+    if !ambiguous.is_empty() {
+        bail!(
+            "Both a directory and its backup exist, keeping both:\n  {}\nDelete the one you don't want, then rename the backup if needed.",
+            ambiguous.join("\n  ")
+        );
+    }
+    Ok(())
+}
+
+/// `<parent>/~<name>`
+fn backup_path(dir: &Path) -> Result<PathBuf> {
     let dir_name = dir
         .file_name()
         .ok_or_else(|| anyhow!("Invalid path: no filename in {}", dir.display()))?;
 
-    let backup_name = format!("{}{}", BACKUP_PREFIX, dir_name.to_string_lossy());
-
     let parent = dir
         .parent()
         .ok_or_else(|| anyhow!("Invalid path: no parent for {}", dir.display()))?;
 
-    let backup_path = parent.join(&backup_name);
-
-    if backup_path.exists() {
-        println!(
-            "  (Backup {} already exists, using it)",
-            backup_path.display()
-        );
-
-        // Clean the current dir (dev environment)
-        if let Err(e) = fs::remove_dir_all(dir) {
-            return Err(anyhow!(
-                "Failed to remove {}: {}\nMake sure the game is closed!",
-                dir.display(),
-                e
-            ));
-        }
+    Ok(parent.join(format!("{}{}", BACKUP_PREFIX, dir_name.to_string_lossy())))
+}
 
-        return Ok(backup_path);
+/// Rename a directory to its backup and create a fresh one in its place
+fn backup_dir(dir: &Path, backup: &Path) -> Result<()> {
+    if backup.exists() {
+        bail!("Backup {} already exists", backup.display());
     }
 
     // Rename original to backup
-    if let Err(e) = fs::rename(dir, &backup_path) {
+    if let Err(e) = fs::rename(dir, backup) {
         return Err(anyhow!(
             "Failed to backup {}: {}\nMake sure the game is closed!",
             dir.display(),
@@ -76,34 +242,26 @@ pub fn backup_dir(dir: &Path) -> Result<PathBuf> {
     // Create fresh dir
     fs::create_dir_all(dir)?;
 
-    let backup_file_name = backup_path
-        .file_name()
-        .ok_or_else(|| anyhow!("Invalid backup path: {}", backup_path.display()))?;
-
-    println!(
-        "  {} -> {}",
-        dir_name.to_string_lossy().dimmed(),
-        backup_file_name.to_string_lossy().green()
-    );
-
-    Ok(backup_path)
+    print_move(dir, backup);
+    Ok(())
 }
 
-/// Restore a directory from its backup
-pub fn restore_dir(dir: &Path) -> Result<()> {
-    let dir_name = dir
-        .file_name()
-        .ok_or_else(|| anyhow!("Invalid path: no filename in {}", dir.display()))?;
-
-    let backup_name = format!("{}{}", BACKUP_PREFIX, dir_name.to_string_lossy());
-
-    let parent = dir
-        .parent()
-        .ok_or_else(|| anyhow!("Invalid path: no parent for {}", dir.display()))?;
-
-    let backup_path = parent.join(&backup_name);
+fn remove_installed(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    } else {
+        return Ok(());
+    }
+    println!("  {} {}", "removed".dimmed(), path.display());
+    Ok(())
+}
 
-    if !backup_path.exists() {
+/// Replace the dev directory with its backup. A missing backup means the
+/// directory was never moved (or is already restored).
+fn restore_dir(dir: &Path, backup: &Path) -> Result<()> {
+    if !backup.exists() {
         return Ok(());
     }
 
@@ -125,17 +283,48 @@ pub fn restore_dir(dir: &Path) -> Result<()> {
         }
     }
 
-    fs::rename(&backup_path, dir)?;
+    fs::rename(backup, dir)?;
 
-    let backup_file_name = backup_path
-        .file_name()
-        .ok_or_else(|| anyhow!("Invalid backup path: {}", backup_path.display()))?;
+    print_move(backup, dir);
+    Ok(())
+}
 
-    println!(
-        "  {} -> {}",
-        backup_file_name.to_string_lossy().green(),
-        dir_name.to_string_lossy().dimmed()
-    );
+fn print_move(from: &Path, to: &Path) {
+    let name = |p: &Path| {
+        p.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    println!("  {} -> {}", name(from).dimmed(), name(to).green());
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_after_crash() {
+        let game = std::env::temp_dir().join(format!("odrill-isolation-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&game);
+        let mods = game.join("mods");
+        fs::create_dir_all(mods.join("user-mod")).unwrap();
+        fs::write(mods.join("user-mod/mod.txt"), "{}").unwrap();
+        let dirs = [mods.clone(), game.join("assets/mod_overrides")];
+
+        // A session killed without running its drop leaves the journal behind
+        let guard = IsolationGuard::acquire(&game, &dirs).unwrap();
+        std::mem::forget(guard);
+        fs::write(mods.join("dev.txt"), "dev").unwrap();
+        assert!(Journal::path(&game).is_file());
+        assert!(IsolationGuard::acquire(&game, &dirs).is_err());
+
+        assert!(restore(&game).unwrap());
+        let restored = mods.join("user-mod/mod.txt").is_file() && !mods.join("dev.txt").exists();
+        let clean = !Journal::path(&game).exists() && !game.join("~mods").exists();
+        assert!(!restore(&game).unwrap());
+        fs::remove_dir_all(&game).unwrap();
+
+        assert!(restored);
+        assert!(clean);
+    }
 }
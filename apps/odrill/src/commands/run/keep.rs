@@ -0,0 +1,93 @@
+//! Installed mods kept in mods/ during an isolated session
+
+use super::copy_dir_all;
+use crate::config_global::GlobalConfig;
+use anyhow::{Result, bail};
+use colored::Colorize;
+use globset::{GlobBuilder, GlobMatcher};
+use pkg::manifest::RunConfig;
+use std::path::Path;
+
+/// The BLT hook, BeardLib and mod saves are always kept
+const DEFAULT_KEEP: [&str; 3] = ["base", "BeardLib", "saves"];
+
+/// `[run] keep` plus the keep list of the selected global profile
+pub fn keep_patterns(run: &RunConfig, profile: Option<&str>) -> Result<Vec<String>> {
+    let mut patterns = run.keep.clone();
+
+    if let Some(name) = profile.or(run.profile.as_deref()) {
+        let config = GlobalConfig::load()?;
+        let Some(profile) = config.profiles.get(name) else {
+            let known: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                bail!(
+                    "Unknown run profile '{}': no [profiles] in the global config",
+                    name
+                );
+            }
+            bail!(
+                "Unknown run profile '{}' (available: {})",
+                name,
+                known.join(", ")
+            );
+        };
+        println!("  Using profile {}", name.cyan());
+        patterns.extend(profile.keep.iter().cloned());
+    }
+    Ok(patterns)
+}
+
+/// Copy the default mods and those matching `patterns` from the backup,
+/// warning about patterns that match nothing
+pub fn copy_kept_mods(mods_backup: &Path, mods_dir: &Path, patterns: &[String]) -> Result<()> {
+    let matchers: Vec<GlobMatcher> = patterns
+        .iter()
+        .map(|p| {
+            // Mod folders live on case-insensitive filesystems on Windows
+            let glob = GlobBuilder::new(p).case_insensitive(true).build()?;
+            Ok(glob.compile_matcher())
+        })
+        .collect::<Result<_>>()?;
+    let mut matched = vec![false; patterns.len()];
+
+    let mut entries: Vec<_> = match std::fs::read_dir(mods_backup) {
+        Ok(entries) => entries.flatten().filter(|e| e.path().is_dir()).collect(),
+        Err(_) => Vec::new(),
+    };
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let mut keep = DEFAULT_KEEP.contains(&name.as_str());
+        for (i, matcher) in matchers.iter().enumerate() {
+            if matcher.is_match(&name) {
+                matched[i] = true;
+                keep = true;
+            }
+        }
+        if keep {
+            println!("  Keeping {}/", name);
+            copy_dir_all(&entry.path(), &mods_dir.join(&name))?;
+        }
+    }
+
+    if !mods_backup.join("base").is_dir() {
+        println!(
+            "{}",
+            "⚠️  Warning: 'base' folder not found. BLT might not work.".yellow()
+        );
+    }
+    for (pattern, _) in patterns.iter().zip(matched).filter(|(_, m)| !m) {
+        println!(
+            "{}",
+            format!(
+                "⚠️  Warning: Kept mod '{}' not found in {}",
+                pattern,
+                mods_backup.display()
+            )
+            .yellow()
+        );
+    }
+
+    Ok(())
+}
@@ -1,12 +1,19 @@
 //! odrill run - Dev launcher with mod isolation
 
 mod game_detection;
+mod hot;
 mod isolation;
+mod keep;
 mod log_watcher;
 mod utils;
 
+use crate::constants::{BACKUP_PREFIX, DEV_INSTALL_MARKER};
 use anyhow::{Context, Result, anyhow};
+use clap::Args;
 use colored::Colorize;
+use pkg::OdrillProject;
+use pkg::manifest::TargetKind;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 pub use game_detection::detect_game_path;
@@ -14,44 +21,124 @@ pub use isolation::IsolationGuard;
 pub use log_watcher::spawn_log_watcher;
 pub use utils::{copy_dir_all, copy_dist_to};
 
-/// Run command - Build, install, and launch Payday 2 with mod isolation
-pub fn run(path: Option<String>) -> Result<()> {
-    // Switch to project directory if specified
-    if let Some(p) = path {
-        switch_to_project_dir(&p)?;
-    }
+#[derive(Args)]
+pub struct RunArgs {
+    /// Project path (optional)
+    pub path: Option<String>,
+
+    /// Put back the mods of an interrupted session and exit
+    #[arg(long)]
+    pub restore: bool,
+
+    /// Global config profile listing more mods to keep
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Install into the real mods/ without moving anything aside
+    #[arg(long, conflicts_with = "profile")]
+    pub no_isolation: bool,
 
+    /// Another local project to build and install in the same session
+    /// (repeatable, adds to `[run] with`)
+    #[arg(long = "with", value_name = "PATH")]
+    pub with: Vec<PathBuf>,
+
+    /// Rebuild hooks as src/ changes and re-run them in the running game
+    #[arg(long)]
+    pub hot: bool,
+}
+
+/// Run command - Build, install, and launch Payday 2 with mod isolation
+pub fn run(args: RunArgs) -> Result<()> {
     let game_path = detect_game_path()?;
     println!("🎮 Found Payday 2 at: {}", game_path.display());
 
     let mods_dir = game_path.join("mods");
     let overrides_dir = game_path.join("assets").join("mod_overrides");
+    let isolated = [mods_dir.clone(), overrides_dir.clone()];
+
+    if args.restore {
+        println!("🧹 Restoring environment...");
+        isolation::restore_interrupted(&game_path, &isolated)?;
+        println!("{}", "✨ Environment restored".green());
+        return Ok(());
+    }
+
+    // `--with` paths are relative to where odrill was started
+    let cwd = std::env::current_dir()?;
+    let with: Vec<PathBuf> = args.with.iter().map(|p| cwd.join(p)).collect();
+
+    // Switch to project directory if specified
+    if let Some(p) = args.path {
+        switch_to_project_dir(&p)?;
+    }
+
+    let project = OdrillProject::load(std::env::current_dir()?)?;
+    let projects = session_projects(&project, &with)?;
 
     // Backup existing mods (Isolation)
-    println!("📦 Backing up existing mods...");
-    let mods_backup = isolation::backup_dir(&mods_dir)?;
-    isolation::backup_dir(&overrides_dir)?;
-
-    // Guard will auto-restore when dropped (end of function or panic)
-    let _guard = IsolationGuard {
-        mods_dir: mods_dir.clone(),
-        overrides_dir: overrides_dir.clone(),
+    // Guard will auto-restore when dropped (end of function, panic or signal).
+    // Without isolation nothing is moved, and it removes what was installed.
+    let guard = if args.no_isolation {
+        println!(
+            "{}",
+            "⚠️  Isolation disabled: installing next to your other mods".yellow()
+        );
+        IsolationGuard::acquire(&game_path, &[])?
+    } else {
+        let keep = keep::keep_patterns(&project.manifest.run, args.profile.as_deref())?;
+        println!("📦 Backing up existing mods...");
+        let guard = IsolationGuard::acquire(&game_path, &isolated)?;
+        let mods_backup = game_path.join(format!("{}mods", BACKUP_PREFIX));
+
+        // Copy kept mods from backup
+        keep::copy_kept_mods(&mods_backup, &mods_dir, &keep)?;
+        guard
+    };
+    let install = Install {
+        mods_dir: &mods_dir,
+        overrides_dir: &overrides_dir,
+        guard: args.no_isolation.then_some(&guard),
     };
 
-    // Copy essential mods from backup
-    copy_essential_mods(&mods_backup, &mods_dir)?;
-
-    // Build current project
-    println!("🔨 Building project...");
-    if let Err(e) = crate::commands::build::run(false, true) {
-        return Err(anyhow!("Build failed: {}", e));
+    // Build every project of the session, the current one last, then
+    // install them. `use <library>` resolves to the session's libraries.
+    let packages = session_packages(&projects);
+    for p in projects.iter().skip(1).chain(projects.first()) {
+        build_project(p, &packages)?;
+    }
+    std::env::set_current_dir(&project.root)?;
+
+    let mut installed = Vec::new();
+    for p in &projects {
+        if p.manifest.target() == TargetKind::Library {
+            println!(
+                "  {} is a library, nothing to install",
+                p.manifest.package.name
+            );
+            continue;
+        }
+        install.project(p)?;
+        installed.push(p.clone());
     }
 
-    // Install to game directory
-    install_to_game(&mods_dir, &overrides_dir)?;
+    // Removed again when dropped, before the isolation guard restores mods/.
+    // Journaled too, so a signal or `--restore` removes it without isolation.
+    let _hot = if args.hot {
+        guard.record_install(&mods_dir.join(hot::HOT_MOD))?;
+        let hot = hot::HotReload::install(&mods_dir)?;
+        hot.watch(&installed, &packages, &mods_dir)?;
+        Some(hot)
+    } else {
+        None
+    };
+    let installed = installed
+        .into_iter()
+        .map(|p| p.manifest.package.name)
+        .collect();
 
     // Launch game
-    launch_game(&game_path)?;
+    launch_game(&game_path, installed)?;
 
     Ok(())
 }
@@ -76,60 +163,168 @@ fn switch_to_project_dir(p: &str) -> Result<()> {
     Ok(())
 }
 
-fn copy_essential_mods(mods_backup: &Path, mods_dir: &Path) -> Result<()> {
-    // Copy 'base' (BLT Hook)
-    let base_src = mods_backup.join("base");
-    if base_src.exists() {
-        println!("  Copying base/ (BLT Hook)...");
-        let base_dest = mods_dir.join("base");
-        copy_dir_all(&base_src, &base_dest)?;
-    } else {
-        println!(
-            "{}",
-            "⚠️  Warning: 'base' folder not found. BLT might not work.".yellow()
-        );
-    }
+/// The current project followed by those from `--with` and `[run] with`,
+/// each once
+fn session_projects(project: &OdrillProject, with: &[PathBuf]) -> Result<Vec<OdrillProject>> {
+    let listed = with.iter().cloned().chain(
+        project
+            .manifest
+            .run
+            .with
+            .iter()
+            .map(|p| project.root.join(p)),
+    );
+
+    let mut roots = vec![project.root.canonicalize()?];
+    let mut projects = vec![project.clone()];
+    for dir in listed {
+        let root = dir
+            .canonicalize()
+            .with_context(|| format!("Project path not found: {}", dir.display()))?;
+        if roots.contains(&root) {
+            continue;
+        }
 
-    // Copy 'BeardLib' (Common dependency)
-    let beardlib_src = mods_backup.join("BeardLib");
-    if beardlib_src.exists() {
-        println!("  Copying BeardLib/...");
-        let beardlib_dest = mods_dir.join("BeardLib");
-        copy_dir_all(&beardlib_src, &beardlib_dest)?;
+        let other = OdrillProject::load(&root)
+            .with_context(|| format!("Failed to load project {}", root.display()))?;
+        let name = &other.manifest.package.name;
+        if let Some(clash) = projects.iter().find(|p| &p.manifest.package.name == name) {
+            anyhow::bail!(
+                "{} and {} are both named '{}'",
+                clash.root.display(),
+                root.display(),
+                name
+            );
+        }
+        roots.push(root);
+        projects.push(other);
     }
 
-    // Copy 'saves' (if exists)
-    let saves_src = mods_backup.join("saves");
-    if saves_src.exists() {
-        let saves_dest = mods_dir.join("saves");
-        copy_dir_all(&saves_src, &saves_dest)?;
-    }
+    Ok(projects)
+}
 
+/// Library projects of the session, by package name
+fn session_packages(projects: &[OdrillProject]) -> BTreeMap<String, PathBuf> {
+    projects
+        .iter()
+        .filter(|p| p.manifest.target() == TargetKind::Library)
+        .map(|p| (p.manifest.package.name.clone(), p.root.clone()))
+        .collect()
+}
+
+fn build_project(project: &OdrillProject, packages: &BTreeMap<String, PathBuf>) -> Result<()> {
+    println!("🔨 Building {}...", project.manifest.package.name);
+    std::env::set_current_dir(&project.root)
+        .context(format!("Failed to set CWD to {}", project.root.display()))?;
+
+    if let Err(e) = crate::commands::build::run(crate::commands::build::BuildArgs {
+        watch: true,
+        packages: packages
+            .iter()
+            .filter(|(name, _)| **name != project.manifest.package.name)
+            .map(|(name, root)| (name.clone(), root.clone()))
+            .collect(),
+        tag_logs: true,
+        ..Default::default()
+    }) {
+        return Err(anyhow!(
+            "Build of {} failed: {}",
+            project.manifest.package.name,
+            e
+        ));
+    }
     Ok(())
 }
 
-fn install_to_game(mods_dir: &Path, overrides_dir: &Path) -> Result<()> {
-    let config = crate::config::OdrillConfig::load()?;
-    let mod_dest = mods_dir.join(&config.package.name);
+/// Where projects are installed. Without isolation, installs go next to the
+/// user's mods: they're journaled by the guard and may only replace earlier
+/// dev installs.
+struct Install<'a> {
+    mods_dir: &'a Path,
+    overrides_dir: &'a Path,
+    guard: Option<&'a IsolationGuard>,
+}
+
+impl Install<'_> {
+    fn project(&self, project: &OdrillProject) -> Result<()> {
+        let mod_dest = self.mods_dir.join(&project.manifest.package.name);
+
+        // Create necessary base folders
+        std::fs::create_dir_all(self.mods_dir)?;
+        std::fs::create_dir_all(self.overrides_dir)?;
+
+        // dist/overrides/<name> is loaded by the game from assets/mod_overrides/<name>
+        let dist_overrides = project
+            .root
+            .join("dist")
+            .join(compiler::overrides::DIST_DIR);
+        let mut overrides = Vec::new();
+        if dist_overrides.is_dir() {
+            for entry in std::fs::read_dir(&dist_overrides)? {
+                overrides.push(self.overrides_dir.join(entry?.file_name()));
+            }
+        }
+
+        // Check everything before touching anything
+        for dest in std::iter::once(&mod_dest).chain(&overrides) {
+            self.check_dest(dest)?;
+        }
+
+        println!("📁 Installing to {}", mod_dest.display());
+        self.record(&mod_dest)?;
+        copy_dist_to(&project.root, &mod_dest)?;
+        mark_dev_install(&mod_dest)?;
+
+        let installed_overrides = mod_dest.join(compiler::overrides::DIST_DIR);
+        for dest in &overrides {
+            println!("📁 Installing override to {}", dest.display());
+            self.record(dest)?;
+            if dest.exists() {
+                std::fs::remove_dir_all(dest)?;
+            }
+            let name = dest.file_name().unwrap_or_default();
+            copy_dir_all(&installed_overrides.join(name), dest)?;
+            mark_dev_install(dest)?;
+        }
+        if installed_overrides.is_dir() {
+            std::fs::remove_dir_all(&installed_overrides)?;
+        }
+
+        Ok(())
+    }
 
-    // Create necessary base folders
-    std::fs::create_dir_all(mods_dir)?;
-    std::fs::create_dir_all(overrides_dir)?;
+    fn check_dest(&self, dest: &Path) -> Result<()> {
+        if self.guard.is_some() && dest.exists() && !dest.join(DEV_INSTALL_MARKER).is_file() {
+            anyhow::bail!(
+                "{} already exists and wasn't installed by odrill run.\n\
+                 Move it out of the way, or run without --no-isolation.",
+                dest.display()
+            );
+        }
+        Ok(())
+    }
 
-    println!("📁 Installing to {}", mod_dest.display());
-    copy_dist_to(&mod_dest)?;
+    fn record(&self, dest: &Path) -> Result<()> {
+        match self.guard {
+            Some(guard) => guard.record_install(dest),
+            None => Ok(()),
+        }
+    }
+}
 
+fn mark_dev_install(dir: &Path) -> Result<()> {
+    std::fs::write(dir.join(DEV_INSTALL_MARKER), "")?;
     Ok(())
 }
 
-fn launch_game(game_path: &Path) -> Result<()> {
+fn launch_game(game_path: &Path, mods: Vec<String>) -> Result<()> {
     use std::process::Command;
 
     println!("🚀 Launching Payday 2...");
     let exe = game_path.join("payday2_win32_release.exe");
 
     // Spawn log watcher
-    spawn_log_watcher(game_path);
+    spawn_log_watcher(game_path, mods);
 
     let mut child = Command::new(&exe)
         .current_dir(game_path)
@@ -23,9 +23,9 @@ pub fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Copy the dist/ directory to the specified destination
-pub fn copy_dist_to(dest: &Path) -> Result<()> {
-    let dist = Path::new("dist");
+/// Copy a project's dist/ directory to the specified destination
+pub fn copy_dist_to(project_root: &Path, dest: &Path) -> Result<()> {
+    let dist = project_root.join("dist");
 
     if !dist.exists() {
         anyhow::bail!("dist/ not found. Run 'odrill build' first.");
@@ -35,6 +35,6 @@ pub fn copy_dist_to(dest: &Path) -> Result<()> {
         fs::remove_dir_all(dest)?;
     }
 
-    copy_dir_all(dist, dest)?;
+    copy_dir_all(&dist, dest)?;
     Ok(())
 }
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -9,6 +10,18 @@ pub struct GlobalConfig {
     pub author: Option<String>,
     pub api_token: Option<String>,
     pub pd2_path: Option<String>,
+    /// Hashlist of known asset paths (for `odrill assets lookup` and override checks)
+    pub hashlist: Option<String>,
+    /// `[profiles.<name>]`: mods kept by `odrill run --profile <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, RunProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RunProfile {
+    /// Installed mod folder names or glob patterns
+    #[serde(default)]
+    pub keep: Vec<String>,
 }
 
 impl GlobalConfig {
@@ -44,6 +57,7 @@ impl GlobalConfig {
             "author" => self.author = Some(value.to_string()),
             "api_token" => self.api_token = Some(value.to_string()),
             "pd2" | "path" => self.pd2_path = Some(value.to_string()),
+            "hashlist" => self.hashlist = Some(value.to_string()),
             _ => anyhow::bail!("Unknown config key: {}", key),
         }
         self.save()?;
@@ -56,6 +70,7 @@ impl GlobalConfig {
             "author" => self.author.clone(),
             "api_token" => self.api_token.clone(),
             "pd2" => self.pd2_path.clone(),
+            "hashlist" => self.hashlist.clone(),
             _ => None,
         }
     }
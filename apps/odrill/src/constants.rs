@@ -5,3 +5,10 @@ pub const DEFAULT_REGISTRY: &str = "http://localhost:5150";
 
 /// Backup prefix for mod isolation
 pub const BACKUP_PREFIX: &str = "~";
+
+/// Journal of the directories moved aside by `odrill run`, in the game dir
+pub const ISOLATION_JOURNAL: &str = "odrill-isolation.json";
+
+/// Written into the mod and override folders `odrill run` installs, so a
+/// later session can tell them from the user's own
+pub const DEV_INSTALL_MARKER: &str = ".odrill-run";
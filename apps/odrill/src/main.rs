@@ -25,28 +25,22 @@ enum Commands {
     /// Create a new project (from template)
     New(commands::new::NewArgs),
 
+    /// Import a BLT/SuperBLT mod folder as a project
+    Import(commands::import::ImportArgs),
+
     /// Build the project (bundle all hooks)
-    Build {
-        /// Force rebuild, ignore cache
-        #[arg(short, long)]
-        force: bool,
-
-        /// Watch for changes and rebuild
-        #[arg(short, long)]
-        watch: bool,
-    },
+    Build(commands::build::BuildArgs),
+
+    /// Check the project (hooks, hook ids, loc keys) without building
+    Check,
 
     /// Clean build artifacts
     Clean,
 
-    /// Add a new hook to the project
+    /// Add a package dependency
     Add {
-        /// Hook ID (e.g., "lib/managers/hudmanagerpd2")
-        hook_id: String,
-
-        /// Output filename
-        #[arg(short, long)]
-        output: Option<String>,
+        /// Package name in the registry
+        package: String,
     },
 
     /// Format Lua code
@@ -63,10 +57,7 @@ enum Commands {
     Install,
 
     /// Run project in isolated dev environment
-    Run {
-        /// Project path (optional)
-        path: Option<String>,
-    },
+    Run(commands::run::RunArgs),
 
     /// Login to registry
     Login,
@@ -79,6 +70,16 @@ enum Commands {
 
     /// Manage templates
     Templates(commands::templates::TemplatesArgs),
+
+    /// Localization tools
+    Loc(commands::loc::LocArgs),
+
+    /// Hook scaffolding and the game script catalogue
+    #[command(alias = "hook")]
+    Hooks(commands::hooks::HooksArgs),
+
+    /// Diesel asset tools (idstring hashes)
+    Assets(commands::assets::AssetsArgs),
 }
 
 fn main() {
@@ -86,13 +87,15 @@ fn main() {
 
     let result = match cli.command {
         Commands::New(args) => commands::new::run(args),
-        Commands::Build { force, watch } => commands::build::run(force, watch),
+        Commands::Import(args) => commands::import::run(args),
+        Commands::Build(args) => commands::build::run(args),
+        Commands::Check => commands::check::run(),
         Commands::Clean => commands::clean::run(),
-        Commands::Add { hook_id, output } => commands::add::run(&hook_id, output.as_deref()),
+        Commands::Add { package } => commands::add::run(&package),
         Commands::Fmt { check } => commands::fmt::run(commands::fmt::FmtArgs { check }),
         Commands::Publish(args) => commands::publish::run(args),
         Commands::Install => commands::install::run(),
-        Commands::Run { path } => commands::run::run(path),
+        Commands::Run(args) => commands::run::run(args),
         Commands::Login => commands::login::run(),
         Commands::Logout => {
             let _ = auth::clear_token();
@@ -101,6 +104,9 @@ fn main() {
         }
         Commands::Config(args) => commands::config::run(args),
         Commands::Templates(args) => commands::templates::run(args),
+        Commands::Loc(args) => commands::loc::run(args),
+        Commands::Hooks(args) => commands::hooks::run(args),
+        Commands::Assets(args) => commands::assets::run(args),
     };
 
     if let Err(e) = result {
@@ -0,0 +1,304 @@
+//! Asset copying from `assets` entries, with include/exclude globs, texture
+//! conversion and an asset manifest of blake3 hashes
+
+mod texture;
+
+pub use texture::{TextureFile, encode_dds};
+
+use anyhow::{Context, bail};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use pkg::manifest::{AssetEntry, OdrillManifest, TextureConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Asset manifest, relative to dist/
+pub const MANIFEST_OUTPUT: &str = "odrill/assets.json";
+
+/// Copied assets and their hashes, written to [`MANIFEST_OUTPUT`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    pub version: String,
+    /// dist-relative path (with `/`) → blake3 hex digest
+    pub files: BTreeMap<String, String>,
+}
+
+impl AssetManifest {
+    /// Manifest of the previous build, empty if missing or unreadable
+    pub fn load(dist_dir: &Path) -> Self {
+        std::fs::read_to_string(dist_dir.join(MANIFEST_OUTPUT))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn write(&self, dist_dir: &Path) -> anyhow::Result<()> {
+        let path = dist_dir.join(MANIFEST_OUTPUT);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// What [`copy`] did
+#[derive(Debug, Default)]
+pub struct CopyReport {
+    pub copied: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+/// A file to copy: source path and its destination relative to dist/
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetFile {
+    pub src: PathBuf,
+    pub dest: PathBuf,
+}
+
+/// Files selected by an `assets` entry
+pub fn resolve(entry: &AssetEntry, project_root: &Path) -> anyhow::Result<Vec<AssetFile>> {
+    let src = project_root.join(&entry.from);
+    let to = entry.to.as_ref().unwrap_or(&entry.from);
+    let include = glob_set(&entry.include)?;
+    let exclude = glob_set(&entry.exclude)?;
+
+    let selected = |relative: &Path| {
+        (entry.include.is_empty() || include.is_match(relative)) && !exclude.is_match(relative)
+    };
+
+    let files: Vec<AssetFile> = if src.is_dir() {
+        WalkDir::new(&src)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let relative = e.path().strip_prefix(&src).ok()?.to_path_buf();
+                selected(&relative).then(|| AssetFile {
+                    src: e.path().to_path_buf(),
+                    dest: to.join(relative),
+                })
+            })
+            .collect()
+    } else if src.is_file() {
+        let name = Path::new(src.file_name().unwrap_or_default());
+        if selected(name) {
+            vec![AssetFile {
+                src: src.clone(),
+                dest: to.clone(),
+            }]
+        } else {
+            Vec::new()
+        }
+    } else {
+        if entry.strict {
+            bail!("Asset not found: {}", src.display());
+        }
+        eprintln!("Warning: Asset not found: {}", src.display());
+        return Ok(Vec::new());
+    };
+
+    if files.is_empty() && entry.strict {
+        bail!("Asset {} matches no file", entry.from.display());
+    }
+
+    Ok(files)
+}
+
+/// Copy every asset and converted texture to dist/, skipping files whose
+/// content is unchanged since the last build and deleting the ones no longer
+/// produced, then write the asset manifest
+pub fn copy(
+    manifest: &OdrillManifest,
+    project_root: &Path,
+    dist_dir: &Path,
+) -> anyhow::Result<CopyReport> {
+    let mut install = Install {
+        previous: AssetManifest::load(dist_dir),
+        current: AssetManifest {
+            version: manifest.package.version.clone(),
+            files: BTreeMap::new(),
+        },
+        report: CopyReport::default(),
+        dist_dir,
+    };
+
+    for asset in &manifest.assets {
+        for file in resolve(&asset.entry(), project_root)? {
+            let content = std::fs::read(&file.src)
+                .with_context(|| format!("Failed to read {}", file.src.display()))?;
+            install.file(&file.dest, &content)?;
+        }
+    }
+
+    for config in &manifest.textures {
+        for file in texture::resolve(config, project_root)? {
+            let content = convert_cached(&file, config, project_root)?;
+            install.file(&file.dest, &content)?;
+        }
+    }
+
+    install.finish()
+}
+
+/// A relative path without `..`, so joining it to dist/ stays inside
+fn inside_dist(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Files written by [`copy`], compared against the previous manifest
+struct Install<'a> {
+    previous: AssetManifest,
+    current: AssetManifest,
+    report: CopyReport,
+    dist_dir: &'a Path,
+}
+
+impl Install<'_> {
+    fn file(&mut self, dest: &Path, content: &[u8]) -> anyhow::Result<()> {
+        if !inside_dist(dest) {
+            bail!(
+                "Asset destination {} must be a relative path inside dist/",
+                dest.display()
+            );
+        }
+        let hash = blake3::hash(content).to_hex().to_string();
+        let key = dest.to_string_lossy().replace('\\', "/");
+        let path = self.dist_dir.join(dest);
+
+        if self.previous.files.get(&key) == Some(&hash) && path.is_file() {
+            self.report.unchanged += 1;
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)?;
+            self.report.copied += 1;
+        }
+
+        self.current.files.insert(key, hash);
+        Ok(())
+    }
+
+    fn finish(mut self) -> anyhow::Result<CopyReport> {
+        for stale in self
+            .previous
+            .files
+            .keys()
+            .filter(|k| !self.current.files.contains_key(*k))
+        {
+            // assets.json may have been edited: never delete outside dist/
+            if !inside_dist(Path::new(stale)) {
+                eprintln!("Warning: Ignoring {} from {}", stale, MANIFEST_OUTPUT);
+                continue;
+            }
+            let path = self.dist_dir.join(stale);
+            if path.is_file() {
+                std::fs::remove_file(path)?;
+                self.report.removed += 1;
+            }
+        }
+
+        if self.current.files.is_empty() {
+            let path = self.dist_dir.join(MANIFEST_OUTPUT);
+            if path.is_file() {
+                std::fs::remove_file(path)?;
+            }
+        } else {
+            self.current.write(self.dist_dir)?;
+        }
+
+        Ok(self.report)
+    }
+}
+
+/// Convert a texture, reusing `target/textures/<hash>.dds` when the image and
+/// settings are unchanged
+fn convert_cached(
+    file: &TextureFile,
+    config: &TextureConfig,
+    project_root: &Path,
+) -> anyhow::Result<Vec<u8>> {
+    let source = std::fs::read(&file.src)
+        .with_context(|| format!("Failed to read {}", file.src.display()))?;
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&source);
+    hasher.update(format!("{:?}:{}", config.format, config.mipmaps).as_bytes());
+    let cache = project_root
+        .join("target")
+        .join("textures")
+        .join(format!("{}.dds", hasher.finalize().to_hex()));
+
+    if let Ok(cached) = std::fs::read(&cache) {
+        return Ok(cached);
+    }
+
+    let dds = texture::convert(&source, &file.src, config.format, config.mipmaps)?;
+    if let Some(parent) = cache.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&cache, &dds)?;
+    Ok(dds)
+}
+
+fn glob_set(patterns: &[String]) -> anyhow::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob '{}'", pattern))?);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_globs() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/EOK");
+        let entry = AssetEntry {
+            from: PathBuf::from("loc"),
+            to: Some(PathBuf::from("strings")),
+            include: vec!["*.json".to_string()],
+            exclude: vec!["french.*".to_string()],
+            strict: true,
+        };
+
+        assert_eq!(
+            resolve(&entry, &root).unwrap(),
+            vec![AssetFile {
+                src: root.join("loc/english.json"),
+                dest: PathBuf::from("strings/english.json"),
+            }]
+        );
+
+        let missing = AssetEntry {
+            from: PathBuf::from("nope"),
+            ..entry
+        };
+        assert!(resolve(&missing, &root).is_err());
+    }
+
+    #[test]
+    fn test_destination_outside_dist() {
+        assert!(inside_dist(Path::new("assets/./gui.texture")));
+        assert!(!inside_dist(Path::new("../outside.txt")));
+        assert!(!inside_dist(Path::new("assets/../../outside.txt")));
+        assert!(!inside_dist(Path::new("/etc/passwd")));
+
+        let dist = std::env::temp_dir().join(format!("odrill-assets-{}", std::process::id()));
+        let mut install = Install {
+            previous: AssetManifest::default(),
+            current: AssetManifest::default(),
+            report: CopyReport::default(),
+            dist_dir: &dist,
+        };
+        assert!(install.file(Path::new("../escape.txt"), b"x").is_err());
+        assert!(!dist.exists());
+    }
+}
@@ -0,0 +1,323 @@
+//! PNG/TGA to DDS conversion for `[[textures]]`
+
+use anyhow::Context;
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+use pkg::manifest::{TextureConfig, TextureFormat};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Image extensions converted from a `[[textures]]` directory
+const IMAGE_EXTENSIONS: &[&str] = &["png", "tga"];
+
+/// An image to convert: source path and its destination relative to dist/
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureFile {
+    pub src: PathBuf,
+    pub dest: PathBuf,
+}
+
+/// Images selected by a `[[textures]]` entry
+pub fn resolve(config: &TextureConfig, project_root: &Path) -> anyhow::Result<Vec<TextureFile>> {
+    let src = project_root.join(&config.from);
+
+    if src.is_file() {
+        let dest = config
+            .to
+            .clone()
+            .unwrap_or_else(|| config.from.with_extension("texture"));
+        return Ok(vec![TextureFile { src, dest }]);
+    }
+
+    if !src.is_dir() {
+        anyhow::bail!("Texture not found: {}", src.display());
+    }
+
+    let to = config.to.as_ref().unwrap_or(&config.from);
+    Ok(WalkDir::new(&src)
+        .sort_by_file_name()
+        .into_iter()
+        .flatten()
+        .filter(|e| {
+            e.file_type().is_file()
+                && e.path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(&src).ok()?;
+            Some(TextureFile {
+                src: e.path().to_path_buf(),
+                dest: to.join(relative).with_extension("texture"),
+            })
+        })
+        .collect())
+}
+
+/// Decode an image and encode it as DDS
+pub fn convert(
+    source: &[u8],
+    path: &Path,
+    format: TextureFormat,
+    mipmaps: bool,
+) -> anyhow::Result<Vec<u8>> {
+    let image = image::load_from_memory(source)
+        .with_context(|| format!("Failed to decode {}", path.display()))?
+        .to_rgba8();
+
+    Ok(encode_dds(&image, format, mipmaps))
+}
+
+/// Encode an image as DDS, with a full mip chain if `mipmaps`
+pub fn encode_dds(image: &RgbaImage, format: TextureFormat, mipmaps: bool) -> Vec<u8> {
+    let (width, height) = image.dimensions();
+    let mut levels = vec![image.clone()];
+    if mipmaps {
+        let (mut w, mut h) = (width, height);
+        while w > 1 || h > 1 {
+            w = (w / 2).max(1);
+            h = (h / 2).max(1);
+            levels.push(imageops::resize(image, w, h, FilterType::Triangle));
+        }
+    }
+
+    let mut data = header(width, height, levels.len() as u32, format);
+    for level in &levels {
+        match format {
+            TextureFormat::Dxt1 => encode_blocks(level, 8, &mut data),
+            TextureFormat::Dxt5 => encode_blocks(level, 16, &mut data),
+            TextureFormat::Uncompressed => {
+                for pixel in level.pixels() {
+                    let [r, g, b, a] = pixel.0;
+                    data.extend_from_slice(&[b, g, r, a]);
+                }
+            }
+        }
+    }
+    data
+}
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+fn header(width: u32, height: u32, mip_count: u32, format: TextureFormat) -> Vec<u8> {
+    let blocks = width.div_ceil(4) * height.div_ceil(4);
+    let (size_flag, pitch_or_size) = match format {
+        TextureFormat::Dxt1 => (DDSD_LINEARSIZE, blocks * 8),
+        TextureFormat::Dxt5 => (DDSD_LINEARSIZE, blocks * 16),
+        TextureFormat::Uncompressed => (DDSD_PITCH, width * 4),
+    };
+    let mip_flag = if mip_count > 1 { DDSD_MIPMAPCOUNT } else { 0 };
+    let caps = if mip_count > 1 {
+        DDSCAPS_TEXTURE | DDSCAPS_COMPLEX | DDSCAPS_MIPMAP
+    } else {
+        DDSCAPS_TEXTURE
+    };
+
+    let mut fields = vec![
+        124,
+        DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT | size_flag | mip_flag,
+        height,
+        width,
+        pitch_or_size,
+        0,
+        mip_count,
+    ];
+    fields.extend([0; 11]);
+
+    // DDS_PIXELFORMAT
+    fields.push(32);
+    match format {
+        TextureFormat::Dxt1 | TextureFormat::Dxt5 => {
+            let fourcc = if format == TextureFormat::Dxt1 {
+                b"DXT1"
+            } else {
+                b"DXT5"
+            };
+            fields.extend([DDPF_FOURCC, u32::from_le_bytes(*fourcc), 0, 0, 0, 0, 0]);
+        }
+        TextureFormat::Uncompressed => fields.extend([
+            DDPF_RGB | DDPF_ALPHAPIXELS,
+            0,
+            32,
+            0x00ff_0000,
+            0x0000_ff00,
+            0x0000_00ff,
+            0xff00_0000,
+        ]),
+    }
+
+    fields.extend([caps, 0, 0, 0, 0]);
+
+    let mut data = b"DDS ".to_vec();
+    for field in fields {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data
+}
+
+/// Encode 4x4 blocks: 8 bytes (DXT1) or 16 bytes (DXT5) each. Edge blocks
+/// repeat the last row/column.
+fn encode_blocks(image: &RgbaImage, block_size: usize, out: &mut Vec<u8>) {
+    let (width, height) = image.dimensions();
+
+    for by in (0..height).step_by(4) {
+        for bx in (0..width).step_by(4) {
+            let mut block = [[0u8; 4]; 16];
+            for (i, pixel) in block.iter_mut().enumerate() {
+                let x = (bx + i as u32 % 4).min(width - 1);
+                let y = (by + i as u32 / 4).min(height - 1);
+                *pixel = image.get_pixel(x, y).0;
+            }
+
+            if block_size == 16 {
+                encode_alpha_block(&block, out);
+                encode_color_block(&block, false, out);
+            } else {
+                encode_color_block(&block, true, out);
+            }
+        }
+    }
+}
+
+fn to_565(c: [u8; 3]) -> u16 {
+    ((c[0] as u16 >> 3) << 11) | ((c[1] as u16 >> 2) << 5) | (c[2] as u16 >> 3)
+}
+
+fn from_565(c: u16) -> [i32; 3] {
+    let r = ((c >> 11) & 0x1f) as i32;
+    let g = ((c >> 5) & 0x3f) as i32;
+    let b = (c & 0x1f) as i32;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    ]
+}
+
+/// BC1 color block from the bounding box of the block's colors. With
+/// `punch_alpha`, pixels with alpha < 128 use the transparent index.
+fn encode_color_block(block: &[[u8; 4]; 16], punch_alpha: bool, out: &mut Vec<u8>) {
+    let transparent = |p: &[u8; 4]| punch_alpha && p[3] < 128;
+    let has_transparent = block.iter().any(transparent);
+
+    let mut min = [255u8; 3];
+    let mut max = [0u8; 3];
+    for pixel in block.iter().filter(|p| !transparent(p)) {
+        for c in 0..3 {
+            min[c] = min[c].min(pixel[c]);
+            max[c] = max[c].max(pixel[c]);
+        }
+    }
+    if min[0] > max[0] {
+        // Fully transparent block
+        min = [0; 3];
+        max = [0; 3];
+    }
+
+    let (mut c0, mut c1) = (to_565(max), to_565(min));
+    // Four-color mode needs c0 > c1, three-color (transparent) mode c0 <= c1
+    if has_transparent == (c0 > c1) {
+        std::mem::swap(&mut c0, &mut c1);
+    }
+
+    let (e0, e1) = (from_565(c0), from_565(c1));
+    let lerp = |a: i32, b: i32, n: i32, d: i32| (a * (d - n) + b * n) / d;
+    let palette: Vec<[i32; 3]> = if c0 > c1 {
+        vec![
+            e0,
+            e1,
+            std::array::from_fn(|c| lerp(e0[c], e1[c], 1, 3)),
+            std::array::from_fn(|c| lerp(e0[c], e1[c], 2, 3)),
+        ]
+    } else {
+        vec![e0, e1, std::array::from_fn(|c| lerp(e0[c], e1[c], 1, 2))]
+    };
+
+    let mut indices = 0u32;
+    for (i, pixel) in block.iter().enumerate() {
+        let index = if transparent(pixel) {
+            3
+        } else {
+            nearest(
+                palette
+                    .iter()
+                    .map(|p| (0..3).map(|c| (p[c] - pixel[c] as i32).pow(2)).sum::<i32>()),
+            )
+        };
+        indices |= (index as u32) << (i * 2);
+    }
+
+    out.extend_from_slice(&c0.to_le_bytes());
+    out.extend_from_slice(&c1.to_le_bytes());
+    out.extend_from_slice(&indices.to_le_bytes());
+}
+
+/// BC3 alpha block in 8-value mode between the block's min and max alpha
+fn encode_alpha_block(block: &[[u8; 4]; 16], out: &mut Vec<u8>) {
+    let a0 = block.iter().map(|p| p[3]).max().unwrap_or(255) as i32;
+    let a1 = block.iter().map(|p| p[3]).min().unwrap_or(255) as i32;
+
+    let mut palette = vec![a0, a1];
+    palette.extend((1..7).map(|n| (a0 * (7 - n) + a1 * n) / 7));
+
+    let mut indices = 0u64;
+    for (i, pixel) in block.iter().enumerate() {
+        let index = if a0 == a1 {
+            0
+        } else {
+            nearest(palette.iter().map(|a| (a - pixel[3] as i32).abs()))
+        };
+        indices |= (index as u64) << (i * 3);
+    }
+
+    out.push(a0 as u8);
+    out.push(a1 as u8);
+    out.extend_from_slice(&indices.to_le_bytes()[..6]);
+}
+
+fn nearest(distances: impl Iterator<Item = i32>) -> usize {
+    distances
+        .enumerate()
+        .min_by_key(|(_, d)| *d)
+        .map_or(0, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_dds() {
+        let image = RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255]));
+
+        let dxt5 = encode_dds(&image, TextureFormat::Dxt5, true);
+        assert_eq!(&dxt5[..4], b"DDS ");
+        assert_eq!(&dxt5[84..88], b"DXT5");
+        // 8x8, 4x4, 2x2 and 1x1 levels: 4 + 1 + 1 + 1 blocks
+        assert_eq!(dxt5.len(), 128 + 7 * 16);
+        assert_eq!(u32::from_le_bytes(dxt5[28..32].try_into().unwrap()), 4);
+
+        let dxt1 = encode_dds(&image, TextureFormat::Dxt1, false);
+        assert_eq!(dxt1.len(), 128 + 4 * 8);
+        // Pure red endpoint, all pixels on it
+        assert_eq!(&dxt1[128..130], &0xf800u16.to_le_bytes());
+        assert_eq!(&dxt1[132..136], &[0, 0, 0, 0]);
+
+        let raw = encode_dds(&image, TextureFormat::Uncompressed, false);
+        assert_eq!(raw.len(), 128 + 8 * 8 * 4);
+        assert_eq!(&raw[128..132], &[0, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,314 @@
+//! BeardLib output target: mod.txt plus main.xml modules from `[beardlib]`
+
+mod xml;
+
+pub use xml::Element;
+
+use crate::localization::{self, Language};
+use crate::output::{Target, check_files, write_generated};
+use crate::superblt::{ModTxt, copy_assets, write_mod_txt};
+use pkg::OdrillProject;
+use pkg::manifest::{AddFileConfig, BeardLibOptionsConfig, HookKind};
+use std::path::{Path, PathBuf};
+
+/// Contents of a BeardLib main.xml
+#[derive(Debug, Clone, Default)]
+pub struct MainXml {
+    pub name: String,
+    pub author: String,
+    pub priority: Option<i32>,
+    pub global_key: Option<String>,
+    pub hooks: Vec<MainXmlHook>,
+    pub add_files_directory: String,
+    pub add_files: Vec<AddFileConfig>,
+    pub localization: Vec<LocalizationModule>,
+    pub options: Option<BeardLibOptionsConfig>,
+    pub asset_updates: Option<AssetUpdates>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MainXmlHook {
+    pub pre: bool,
+    pub source_file: String,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizationModule {
+    pub directory: String,
+    pub default: String,
+    pub languages: Vec<Language>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetUpdates {
+    pub id: String,
+    pub provider: String,
+    pub version: String,
+}
+
+/// SuperBLT mod whose hooks, assets and options are declared in main.xml
+pub struct BeardLibTarget;
+
+impl Target for BeardLibTarget {
+    fn name(&self) -> &str {
+        "beardlib"
+    }
+
+    fn generate(&self, project: &OdrillProject, dist_dir: &Path) -> anyhow::Result<Vec<String>> {
+        let manifest = &project.manifest;
+        let asset_files = copy_assets(manifest, &project.root, dist_dir)?;
+        localization::check(&manifest.localization, &project.root)?;
+        let generated_files = write_generated(project, dist_dir)?;
+
+        MainXml::from_project(project).write(dist_dir)?;
+
+        // BeardLib loads hooks from main.xml; persist scripts stay in mod.txt
+        let mut mod_txt = ModTxt::from_manifest(manifest);
+        mod_txt.hooks.clear();
+        mod_txt.pre_hooks.clear();
+        write_mod_txt(&mod_txt, dist_dir)?;
+
+        let mut generated = vec!["mod.txt".to_string(), "main.xml".to_string()];
+        generated.extend(generated_files);
+        generated.extend(asset_files);
+        Ok(generated)
+    }
+}
+
+impl MainXml {
+    pub fn from_project(project: &OdrillProject) -> Self {
+        let manifest = &project.manifest;
+        let pkg = &manifest.package;
+        let beardlib = manifest.beardlib.clone().unwrap_or_default();
+
+        let all_hooks = crate::output::mod_hooks(manifest);
+        let mut hooks: Vec<_> = all_hooks
+            .iter()
+            .filter(|h| h.kind != HookKind::Persist)
+            .collect();
+        hooks.sort_by_key(|h| std::cmp::Reverse(h.priority));
+
+        Self {
+            name: pkg.name.clone(),
+            author: pkg.authors.join(", "),
+            priority: beardlib.priority,
+            global_key: beardlib.global_key,
+            hooks: hooks
+                .into_iter()
+                .map(|h| MainXmlHook {
+                    pre: h.kind == HookKind::Pre,
+                    source_file: match h.kind {
+                        HookKind::Wildcard => "*".to_string(),
+                        _ => h.id.clone(),
+                    },
+                    file: h.output.to_string_lossy().replace('\\', "/"),
+                })
+                .collect(),
+            add_files_directory: beardlib.add_files_directory,
+            add_files: beardlib.add_files,
+            localization: manifest
+                .localization
+                .iter()
+                .map(|l| LocalizationModule {
+                    directory: l.directory.clone(),
+                    default: l.default.clone(),
+                    languages: localization::languages(l, &project.root),
+                })
+                .collect(),
+            options: beardlib.options,
+            asset_updates: beardlib.asset_updates.map(|u| AssetUpdates {
+                id: u.id,
+                provider: u.provider,
+                version: pkg.version.clone(),
+            }),
+        }
+    }
+
+    pub fn to_element(&self) -> Element {
+        let mut root = Element::new("mod")
+            .attr("name", &self.name)
+            .attr("author", &self.author)
+            .attr_opt("priority", self.priority)
+            .attr_opt("global_key", self.global_key.as_ref());
+
+        if !self.hooks.is_empty() {
+            let mut hooks = Element::new("Hooks");
+            for hook in &self.hooks {
+                hooks = hooks.child(
+                    Element::new(if hook.pre { "pre" } else { "post" })
+                        .attr("source_file", &hook.source_file)
+                        .attr("file", &hook.file),
+                );
+            }
+            root = root.child(hooks);
+        }
+
+        if !self.add_files.is_empty() {
+            let mut add_files =
+                Element::new("AddFiles").attr("directory", &self.add_files_directory);
+            for file in &self.add_files {
+                add_files = add_files.child(Element::new(&file.file_type).attr("path", &file.path));
+            }
+            root = root.child(add_files);
+        }
+
+        for loc in &self.localization {
+            let mut module = Element::new("Localization")
+                .attr("directory", &loc.directory)
+                .attr("default", &loc.default);
+            for language in &loc.languages {
+                module = module.child(
+                    Element::new("loc")
+                        .attr("language", &language.language)
+                        .attr("file", &language.file),
+                );
+            }
+            root = root.child(module);
+        }
+
+        if let Some(options) = &self.options {
+            let mut list = Element::new("options");
+            for option in &options.options {
+                let default = match &option.default {
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                list = list.child(
+                    Element::new("option")
+                        .attr("name", &option.name)
+                        .attr("type", &option.option_type)
+                        .attr("default_value", default),
+                );
+            }
+            root = root.child(
+                Element::new("Options")
+                    .attr("build_menu", options.build_menu)
+                    .attr_opt("save_file", options.save_file.as_ref())
+                    .child(list),
+            );
+        }
+
+        if let Some(updates) = &self.asset_updates {
+            root = root.child(
+                Element::new("AssetUpdates")
+                    .attr("id", &updates.id)
+                    .attr("provider", &updates.provider)
+                    .attr("version", &updates.version),
+            );
+        }
+
+        root
+    }
+
+    pub fn to_xml(&self) -> String {
+        self.to_element().render()
+    }
+
+    /// Files main.xml points to, relative to the mod folder
+    pub fn referenced_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.hooks.iter().map(|h| PathBuf::from(&h.file)).collect();
+
+        files.extend(self.add_files.iter().map(|f| {
+            Path::new(&self.add_files_directory).join(format!("{}.{}", f.path, f.file_type))
+        }));
+
+        for loc in &self.localization {
+            files.extend(
+                loc.languages
+                    .iter()
+                    .map(|l| Path::new(&loc.directory).join(&l.file)),
+            );
+        }
+
+        files
+    }
+
+    /// Write main.xml after checking the files it points to exist
+    pub fn write(&self, dist_dir: &Path) -> anyhow::Result<()> {
+        check_files(dist_dir, "main.xml", self.referenced_files())?;
+        std::fs::write(dist_dir.join("main.xml"), self.to_xml())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_xml_modules() {
+        let manifest: pkg::OdrillManifest = toml::from_str(
+            r#"
+            [package]
+            name = "Beard & Co"
+            version = "1.0.0"
+            authors = ["A"]
+
+            [[localization]]
+            directory = "loc"
+            default = "english.json"
+
+            [beardlib]
+            priority = 5
+
+            [[beardlib.add_files]]
+            type = "texture"
+            path = "guis/textures/icon"
+
+            [beardlib.options]
+            save_file = "beard.txt"
+
+            [[beardlib.options.options]]
+            name = "enabled"
+            type = "bool"
+            default = true
+
+            [beardlib.asset_updates]
+            id = "12345"
+
+            [[hooks]]
+            kind = "pre"
+            id = "lib/entry"
+            entry = "src/pre.lua"
+            output = "hooks/pre.lua"
+            "#,
+        )
+        .unwrap();
+
+        let project = OdrillProject {
+            root: PathBuf::from("/nonexistent"),
+            manifest,
+        };
+        let main_xml = MainXml::from_project(&project);
+        assert_eq!(
+            main_xml.to_xml(),
+            r#"<mod name="Beard &amp; Co" author="A" priority="5">
+    <Hooks>
+        <pre source_file="lib/entry" file="hooks/pre.lua"/>
+    </Hooks>
+    <AddFiles directory="assets">
+        <texture path="guis/textures/icon"/>
+    </AddFiles>
+    <Localization directory="loc" default="english.json">
+        <loc language="english" file="english.json"/>
+    </Localization>
+    <Options build_menu="true" save_file="beard.txt">
+        <options>
+            <option name="enabled" type="bool" default_value="true"/>
+        </options>
+    </Options>
+    <AssetUpdates id="12345" provider="modworkshop" version="1.0.0"/>
+</mod>
+"#
+        );
+        assert_eq!(
+            main_xml.referenced_files(),
+            vec![
+                PathBuf::from("hooks/pre.lua"),
+                PathBuf::from("assets/guis/textures/icon.texture"),
+                PathBuf::from("loc/english.json"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Minimal XML element tree, enough for BeardLib's main.xml
+
+/// An XML element with attributes and child elements
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn attr(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.attrs.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Add an attribute only when `value` is set
+    pub fn attr_opt(self, key: impl Into<String>, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.attr(key, value),
+            None => self,
+        }
+    }
+
+    pub fn child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Render with 4-space indentation
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.write(0, &mut out);
+        out
+    }
+
+    fn write(&self, depth: usize, out: &mut String) {
+        out.push_str(&"    ".repeat(depth));
+        out.push('<');
+        out.push_str(&self.name);
+        for (key, value) in &self.attrs {
+            out.push_str(&format!(" {}=\"{}\"", key, escape(value)));
+        }
+
+        if self.children.is_empty() {
+            out.push_str("/>\n");
+            return;
+        }
+
+        out.push_str(">\n");
+        for child in &self.children {
+            child.write(depth + 1, out);
+        }
+        out.push_str(&"    ".repeat(depth));
+        out.push_str(&format!("</{}>\n", self.name));
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,96 @@
+//! Parsed bundle: the ordered pieces of code making up a hook's output
+
+use std::path::{Path, PathBuf};
+
+/// What a chunk of bundled code comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChunkKind {
+    /// Body of a source file (include/use directives removed)
+    File,
+    /// A symbol imported with `use module::symbol`
+    Symbol { name: String },
+}
+
+/// A piece of code in the bundle
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub kind: ChunkKind,
+    pub source: PathBuf,
+    pub code: String,
+}
+
+/// A bundle before rendering: chunks in output order
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub entry: PathBuf,
+    /// Code emitted after the header, before any chunk
+    pub prelude: String,
+    pub chunks: Vec<Chunk>,
+}
+
+impl Bundle {
+    pub fn new(entry: &Path) -> Self {
+        Self {
+            entry: entry.to_path_buf(),
+            prelude: String::new(),
+            chunks: Vec::new(),
+        }
+    }
+
+    pub fn push_file(&mut self, source: &Path, code: String) {
+        self.chunks.push(Chunk {
+            kind: ChunkKind::File,
+            source: source.to_path_buf(),
+            code,
+        });
+    }
+
+    pub fn push_symbol(&mut self, name: &str, source: &Path, code: String) {
+        self.chunks.push(Chunk {
+            kind: ChunkKind::Symbol {
+                name: name.to_string(),
+            },
+            source: source.to_path_buf(),
+            code,
+        });
+    }
+
+    /// Render the final Lua output. Paths in markers are shown relative to
+    /// `root` so output doesn't depend on where the project is checked out.
+    pub fn render<'a>(&'a self, root: &Path) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!(
+            "-- Bundled by odrill v{}\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+        let relative = |path: &'a Path| path.strip_prefix(root).unwrap_or(path);
+
+        output.push_str(&format!("-- Entry: {}\n", relative(&self.entry).display()));
+        output.push_str("-- DO NOT EDIT - This file is auto-generated\n\n");
+        output.push_str(&self.prelude);
+
+        for chunk in &self.chunks {
+            match &chunk.kind {
+                ChunkKind::Symbol { name } => {
+                    output.push_str(&format!(
+                        "-- [use {}::{}]\n",
+                        relative(&chunk.source).display(),
+                        name
+                    ));
+                    output.push_str(&chunk.code);
+                    output.push('\n');
+                }
+                ChunkKind::File => {
+                    output.push_str(&format!("\n-- [{}]\n", relative(&chunk.source).display()));
+                    for line in chunk.code.lines() {
+                        output.push_str(line);
+                        output.push('\n');
+                    }
+                }
+            }
+        }
+
+        output
+    }
+}
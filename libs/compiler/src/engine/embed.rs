@@ -0,0 +1,257 @@
+//! Compile-time embedding of JSON/TOML data files as Lua table literals
+
+use crate::error::BundlerError;
+use crate::vfs::FileSystem;
+use std::path::Path;
+
+const LUA_KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+/// Data value that can be written as a Lua literal
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LuaValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<LuaValue>),
+    Table(Vec<(String, LuaValue)>),
+}
+
+/// Read a data file and convert it to a Lua table literal
+pub fn load_embed(path: &Path, fs: &dyn FileSystem) -> Result<String, BundlerError> {
+    let content = fs
+        .read_to_string(path)
+        .map_err(|e| BundlerError::FileRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+    let invalid = |message: String| BundlerError::InvalidEmbed {
+        file: path.to_path_buf(),
+        message,
+    };
+
+    let value = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            let json: serde_json::Value =
+                serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+            from_json(&json, "$").map_err(invalid)?
+        }
+        Some("toml") => {
+            let toml: toml::Table = toml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+            from_toml(&toml::Value::Table(toml), "$").map_err(invalid)?
+        }
+        _ => {
+            return Err(invalid(
+                "unsupported file type (expected .json or .toml)".to_string(),
+            ));
+        }
+    };
+
+    Ok(to_lua_literal(&value))
+}
+
+/// Render a value as a Lua literal
+pub(crate) fn to_lua_literal(value: &LuaValue) -> String {
+    let mut output = String::new();
+    write_value(value, 0, &mut output);
+    output
+}
+
+fn from_json(value: &serde_json::Value, at: &str) -> Result<LuaValue, String> {
+    use serde_json::Value;
+
+    Ok(match value {
+        Value::Null => return Err(format!("null at {} cannot be represented in Lua", at)),
+        Value::Bool(b) => LuaValue::Bool(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => LuaValue::Integer(i),
+            None => LuaValue::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => LuaValue::String(s.clone()),
+        Value::Array(items) => LuaValue::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| from_json(v, &format!("{}[{}]", at, i)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => LuaValue::Table(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), from_json(v, &format!("{}.{}", at, k))?)))
+                .collect::<Result<_, String>>()?,
+        ),
+    })
+}
+
+fn from_toml(value: &toml::Value, at: &str) -> Result<LuaValue, String> {
+    use toml::Value;
+
+    Ok(match value {
+        Value::Boolean(b) => LuaValue::Bool(*b),
+        Value::Integer(i) => LuaValue::Integer(*i),
+        Value::Float(f) => LuaValue::Float(*f),
+        Value::String(s) => LuaValue::String(s.clone()),
+        Value::Datetime(dt) => LuaValue::String(dt.to_string()),
+        Value::Array(items) => LuaValue::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| from_toml(v, &format!("{}[{}]", at, i)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Table(map) => LuaValue::Table(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), from_toml(v, &format!("{}.{}", at, k))?)))
+                .collect::<Result<_, String>>()?,
+        ),
+    })
+}
+
+fn write_value(value: &LuaValue, depth: usize, out: &mut String) {
+    match value {
+        LuaValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        LuaValue::Integer(i) => out.push_str(&i.to_string()),
+        LuaValue::Float(f) if f.is_nan() => out.push_str("(0/0)"),
+        LuaValue::Float(f) if f.is_infinite() => {
+            out.push_str(if *f > 0.0 { "math.huge" } else { "-math.huge" })
+        }
+        LuaValue::Float(f) => out.push_str(&format!("{:?}", f)),
+        LuaValue::String(s) => write_string(s, out),
+        LuaValue::Array(items) if items.is_empty() => out.push_str("{}"),
+        LuaValue::Table(entries) if entries.is_empty() => out.push_str("{}"),
+        LuaValue::Array(items) => {
+            out.push_str("{\n");
+            for item in items {
+                out.push_str(&"    ".repeat(depth + 1));
+                write_value(item, depth + 1, out);
+                out.push_str(",\n");
+            }
+            out.push_str(&"    ".repeat(depth));
+            out.push('}');
+        }
+        LuaValue::Table(entries) => {
+            out.push_str("{\n");
+            for (key, item) in entries {
+                out.push_str(&"    ".repeat(depth + 1));
+                if is_identifier(key) {
+                    out.push_str(key);
+                } else {
+                    out.push('[');
+                    write_string(key, out);
+                    out.push(']');
+                }
+                out.push_str(" = ");
+                write_value(item, depth + 1, out);
+                out.push_str(",\n");
+            }
+            out.push_str(&"    ".repeat(depth));
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // Always 3 digits so a following digit isn't read as part of the escape
+            c if c.is_ascii_control() => out.push_str(&format!("\\{:03}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    let starts_ok = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+
+    starts_ok
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !LUA_KEYWORDS.contains(&key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::preprocess::Preprocessors;
+    use crate::engine::processor::{self, Sources};
+    use crate::parser::LuaParser;
+    use crate::vfs::MemoryFs;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_toml_to_lua() {
+        let toml: toml::Table = toml::from_str(
+            r#"
+            tank = 1.5
+            "end" = "x\"y"
+            list = [1, 2]
+            "#,
+        )
+        .unwrap();
+        let value = from_toml(&toml::Value::Table(toml), "$").unwrap();
+        let mut out = String::new();
+        write_value(&value, 0, &mut out);
+
+        assert!(out.contains("tank = 1.5,"));
+        assert!(out.contains(r#"["end"] = "x\"y","#));
+        assert!(out.contains("list = {\n        1,\n        2,\n    },"));
+    }
+
+    #[test]
+    fn test_control_char_escape() {
+        let mut out = String::new();
+        write_string("\u{1}2", &mut out);
+        assert_eq!(out, r#""\0012""#);
+    }
+
+    #[test]
+    fn test_expand_embeds_in_place() {
+        let fs = MemoryFs::new();
+        fs.insert("/project/data.json", r#"{"a": 1}"#);
+        let parser = LuaParser::new("--");
+        let preprocessors = Preprocessors::default();
+        let packages = BTreeMap::new();
+        let sources = Sources {
+            parser: &parser,
+            preprocessors: &preprocessors,
+            fs: &fs,
+            packages: &packages,
+        };
+
+        // The commented-out call is left alone
+        let source =
+            "--[[\nodrill.embed(\"data.json\")\n]]\nlocal d = odrill.embed(\"data.json\")\n";
+        let embeds = parser.extract_embeds(source);
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(embeds[0].line, 4);
+
+        let expanded = processor::expand_embeds(
+            source,
+            Path::new("/project/src/main.lua"),
+            Path::new("/project"),
+            &sources,
+        )
+        .unwrap();
+        assert!(expanded.starts_with("--[[\nodrill.embed(\"data.json\")\n]]\nlocal d = {"));
+    }
+
+    #[test]
+    fn test_json_null_rejected() {
+        let json: serde_json::Value = serde_json::from_str(r#"{"a": [1, null]}"#).unwrap();
+        let err = from_json(&json, "$").unwrap_err();
+        assert!(err.contains("$.a[1]"));
+    }
+}
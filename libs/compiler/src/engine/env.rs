@@ -0,0 +1,154 @@
+//! Build-time constants exposed to bundles as the `odrill_env` table
+
+use crate::engine::embed::{LuaValue, to_lua_literal};
+use pkg::OdrillProject;
+use regex::Regex;
+use std::path::Path;
+use std::process::Command;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static ENV_REFERENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bodrill_env\b").unwrap());
+
+/// Values injected into bundles that reference `odrill_env`
+#[derive(Debug, Clone)]
+pub struct BuildEnv {
+    pub name: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    pub profile: String,
+    /// Unix timestamp (seconds) of the build
+    pub build_time: u64,
+    pub git_commit: Option<String>,
+}
+
+impl BuildEnv {
+    /// Constants from the manifest only: no timestamp, no git commit. Used
+    /// unless a build command calls [`BuildEnv::detect`].
+    pub fn from_manifest(project: &OdrillProject, profile: &str) -> Self {
+        let pkg = &project.manifest.package;
+        Self {
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            authors: pkg.authors.clone(),
+            profile: profile.to_string(),
+            build_time: 0,
+            git_commit: None,
+        }
+    }
+
+    /// Collect build constants for a project.
+    ///
+    /// With `reproducible`, the timestamp is taken from `SOURCE_DATE_EPOCH`,
+    /// then the last commit time, and falls back to 0.
+    pub fn detect(project: &OdrillProject, profile: &str, reproducible: bool) -> Self {
+        let pkg = &project.manifest.package;
+
+        let build_time = if reproducible {
+            std::env::var("SOURCE_DATE_EPOCH")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .or_else(|| {
+                    git(&project.root, &["log", "-1", "--format=%ct"])?
+                        .parse()
+                        .ok()
+                })
+                .unwrap_or(0)
+        } else {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        };
+
+        Self {
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            authors: pkg.authors.clone(),
+            profile: profile.to_string(),
+            build_time,
+            git_commit: git(&project.root, &["rev-parse", "--short", "HEAD"]),
+        }
+    }
+
+    /// Lua declaration of the `odrill_env` table
+    pub fn to_lua(&self) -> String {
+        let mut entries = vec![
+            ("name".to_string(), LuaValue::String(self.name.clone())),
+            (
+                "version".to_string(),
+                LuaValue::String(self.version.clone()),
+            ),
+            (
+                "authors".to_string(),
+                LuaValue::Array(
+                    self.authors
+                        .iter()
+                        .map(|a| LuaValue::String(a.clone()))
+                        .collect(),
+                ),
+            ),
+            (
+                "profile".to_string(),
+                LuaValue::String(self.profile.clone()),
+            ),
+            (
+                "build_time".to_string(),
+                LuaValue::Integer(self.build_time as i64),
+            ),
+        ];
+
+        if let Some(commit) = &self.git_commit {
+            entries.push(("git_commit".to_string(), LuaValue::String(commit.clone())));
+        }
+
+        format!(
+            "local odrill_env = {}\n",
+            to_lua_literal(&LuaValue::Table(entries))
+        )
+    }
+
+    /// Check whether bundled code references `odrill_env`
+    pub fn is_referenced(code: &str) -> bool {
+        ENV_REFERENCE.is_match(code)
+    }
+}
+
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_to_lua() {
+        let env = BuildEnv {
+            name: "eok".to_string(),
+            version: "2.0.0".to_string(),
+            authors: vec![],
+            profile: "release".to_string(),
+            build_time: 0,
+            git_commit: None,
+        };
+        let lua = env.to_lua();
+
+        assert!(lua.starts_with("local odrill_env = {\n"));
+        assert!(lua.contains("version = \"2.0.0\","));
+        assert!(!lua.contains("git_commit"));
+        assert!(BuildEnv::is_referenced("print(odrill_env.version)"));
+        assert!(!BuildEnv::is_referenced("print(my_odrill_env)"));
+    }
+}
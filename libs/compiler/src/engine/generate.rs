@@ -1,30 +1,65 @@
-use crate::engine::{Compiler, processor};
-use crate::error::BundlerError;
+use crate::engine::bundle::{Bundle, ChunkKind};
+use crate::engine::{BuildEnv, Compiler, processor};
+use crate::passes::PassContext;
+use anyhow::Context;
 use std::collections::HashSet;
 use std::path::Path;
 
 impl Compiler {
     /// Generate the bundled output for a hook
     pub fn generate_bundle(&mut self, entry: &Path, src_root: &Path) -> anyhow::Result<String> {
-        let mut output = String::new();
+        let mut bundle = Bundle::new(entry);
         let mut processed = HashSet::new();
         let mut local_symbols = crate::engine::symbols::SymbolTable::new();
 
-        output.push_str(&format!(
-            "-- Bundled by odrill v{}\n",
-            env!("CARGO_PKG_VERSION")
-        ));
-        output.push_str(&format!("-- Entry: {}\n", entry.display()));
-        output.push_str("-- DO NOT EDIT - This file is auto-generated\n\n");
-
         self.bundle_file(
             entry,
             src_root,
-            &mut output,
+            &mut bundle,
             &mut processed,
             &mut local_symbols,
         )?;
 
+        // Inject build constants only when the bundle uses them
+        if bundle
+            .chunks
+            .iter()
+            .any(|c| BuildEnv::is_referenced(&c.code))
+        {
+            bundle.prelude.push_str(&self.env.to_lua());
+        }
+
+        let ctx = PassContext {
+            project: &self.project,
+            entry,
+        };
+
+        for pass in &self.passes {
+            for chunk in &mut bundle.chunks {
+                match &chunk.kind {
+                    ChunkKind::File => pass.transform_file(&ctx, &chunk.source, &mut chunk.code),
+                    ChunkKind::Symbol { name } => {
+                        pass.transform_symbol(&ctx, name, &chunk.source, &mut chunk.code)
+                    }
+                }
+                .with_context(|| {
+                    format!(
+                        "Pass '{}' failed on {}",
+                        pass.name(),
+                        chunk.source.display()
+                    )
+                })?;
+            }
+            pass.transform_bundle(&ctx, &mut bundle)
+                .with_context(|| format!("Pass '{}' failed", pass.name()))?;
+        }
+
+        let mut output = bundle.render(&self.project.root);
+        for pass in &self.passes {
+            pass.transform_output(&ctx, &mut output)
+                .with_context(|| format!("Pass '{}' failed", pass.name()))?;
+        }
+
         Ok(output)
     }
 
@@ -32,21 +67,20 @@ impl Compiler {
         &mut self,
         file: &Path,
         src_root: &Path,
-        output: &mut String,
+        bundle: &mut Bundle,
         processed: &mut HashSet<std::path::PathBuf>,
         local_symbols: &mut crate::engine::symbols::SymbolTable,
     ) -> anyhow::Result<()> {
-        let canonical = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        let canonical = self.read_fs.canonicalize(file);
 
         if processed.contains(&canonical) {
             return Ok(());
         }
         processed.insert(canonical.clone());
 
-        let content = std::fs::read_to_string(file).map_err(|e| BundlerError::FileRead {
-            path: file.to_path_buf(),
-            source: e,
-        })?;
+        let sources = self.sources();
+        let content = sources.read(file)?;
+        let content = processor::expand_embeds(&content, file, &self.project.root, &sources)?;
 
         // Process use directives first
         let uses = self.parser.extract_uses(&content);
@@ -54,47 +88,37 @@ impl Compiler {
         processor::process_use_directives(
             &uses,
             src_root,
-            &self.parser,
+            &sources,
             local_symbols,
-            output,
+            bundle,
             &mut visited_modules,
         )?;
 
         // Process traditional includes
         let includes = self.parser.extract_includes(&content);
         for inc in &includes {
-            if let Some(resolved) =
-                self.parser
-                    .resolve_module_path(&inc.module_path, file, src_root)
-            {
-                self.bundle_file(&resolved, src_root, output, processed, local_symbols)?;
+            if let Some(resolved) = self.parser.resolve_module_path(
+                &inc.module_path,
+                file,
+                src_root,
+                self.read_fs.as_ref(),
+            ) {
+                self.bundle_file(&resolved, src_root, bundle, processed, local_symbols)?;
             }
         }
 
         // Add file content (filter out include/use lines)
-        let relative = file.strip_prefix(&self.project.root).unwrap_or(file);
-        output.push_str(&format!("\n-- [{}]\n", relative.display()));
-
+        let mut code = String::new();
         for line in content.lines() {
             let is_directive = includes.iter().any(|i| line.contains(&i.full_match))
                 || uses.iter().any(|u| line.contains(&u.full_match));
             if !is_directive {
-                output.push_str(line);
-                output.push('\n');
+                code.push_str(line);
+                code.push('\n');
             }
         }
+        bundle.push_file(file, code);
 
         Ok(())
     }
-
-    pub fn apply_options(&self, code: String) -> String {
-        // Use manifest.options (assuming OptionsConfig has strict types field)
-        // For now assume default, or check manifest
-        // let options = &self.project.manifest.options;
-        // if options.strip_comments ...
-
-        // TODO: Implement options in OdrillManifest properly
-
-        code
-    }
 }
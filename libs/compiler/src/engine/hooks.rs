@@ -0,0 +1,220 @@
+use super::Compiler;
+use crate::hooks::PARTIAL_NOTE;
+use anyhow::bail;
+use pkg::manifest::{CheckLevel, HookKind};
+use std::path::PathBuf;
+
+/// A `[[hooks]]` id missing from the hook catalogue
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownHookId {
+    pub id: String,
+    pub suggestion: Option<String>,
+}
+
+/// A game method hooked or overridden by the project but missing from the class index
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownMethod {
+    pub class: String,
+    pub method: String,
+    pub file: PathBuf,
+    pub line: usize,
+    pub suggestion: Option<String>,
+}
+
+impl Compiler {
+    /// Ids of `pre` / `post` hooks that aren't known game scripts
+    pub fn unknown_hook_ids(&self) -> Vec<UnknownHookId> {
+        let mut unknown: Vec<UnknownHookId> = Vec::new();
+        for hook in &self.project.manifest.hooks {
+            if !matches!(hook.kind, HookKind::Pre | HookKind::Post)
+                || self.hook_catalogue.contains(&hook.id)
+                || unknown.iter().any(|u| u.id == hook.id)
+            {
+                continue;
+            }
+            unknown.push(UnknownHookId {
+                id: hook.id.clone(),
+                suggestion: self.hook_catalogue.suggest(&hook.id).map(str::to_string),
+            });
+        }
+        unknown
+    }
+
+    /// Report unknown hook ids as set by `[options] hook_ids`. Unset, only a
+    /// full catalogue is checked against, since the bundled one is partial.
+    pub fn check_hook_ids(&self) -> anyhow::Result<()> {
+        let partial = self.hook_catalogue.is_partial();
+        let level = match self.project.manifest.options.hook_ids {
+            Some(level) => level,
+            None if partial => return Ok(()),
+            None => CheckLevel::Warn,
+        };
+        if level == CheckLevel::Off {
+            return Ok(());
+        }
+
+        let unknown = self.unknown_hook_ids();
+        let describe = |u: &UnknownHookId| match &u.suggestion {
+            Some(suggestion) => format!("'{}' (did you mean '{}'?)", u.id, suggestion),
+            None => format!("'{}'", u.id),
+        };
+        let note = if partial {
+            format!(" ({})", PARTIAL_NOTE)
+        } else {
+            String::new()
+        };
+
+        match level {
+            CheckLevel::Error if !unknown.is_empty() => bail!(
+                "Unknown hook ids: {}{}",
+                unknown.iter().map(describe).collect::<Vec<_>>().join(", "),
+                note
+            ),
+            _ => {
+                for id in &unknown {
+                    eprintln!("Warning: Unknown hook id {}{}", describe(id), note);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Methods of indexed game classes that the project hooks with
+    /// `Hooks:PostHook` / `PreHook` / `OverrideFunction` but that don't exist.
+    /// `function Class:method` definitions only count when they look like a
+    /// typo of an existing method, since mods may add their own.
+    pub fn unknown_methods(&self) -> anyhow::Result<Vec<UnknownMethod>> {
+        let Some(index) = &self.class_index else {
+            return Ok(Vec::new());
+        };
+        let sources = self.sources();
+
+        let mut unknown = Vec::new();
+        for file in self.entry_sources()? {
+            let content = sources.read(&file)?;
+
+            let hooked = self
+                .parser
+                .extract_method_hooks(&content)
+                .into_iter()
+                .map(|h| (h.class, h.method, h.line, true));
+            let defined = self
+                .parser
+                .extract_methods(&content)
+                .into_iter()
+                .map(|m| (m.class, m.method, m.line, false));
+
+            for (class, method, line, is_hook) in hooked.chain(defined) {
+                if index.class(&class).is_none() || index.method(&class, &method).is_some() {
+                    continue;
+                }
+                let suggestion = index.suggest_method(&class, &method).map(str::to_string);
+                if !is_hook && suggestion.is_none() {
+                    continue;
+                }
+                unknown.push(UnknownMethod {
+                    class,
+                    method,
+                    file: file.clone(),
+                    line,
+                    suggestion,
+                });
+            }
+        }
+
+        Ok(unknown)
+    }
+
+    /// Report hooked methods missing from the class index as set by
+    /// `[options] hook_ids`
+    pub fn check_methods(&self) -> anyhow::Result<()> {
+        let level = self.project.manifest.options.hook_ids.unwrap_or_default();
+        if level == CheckLevel::Off {
+            return Ok(());
+        }
+
+        let unknown = self.unknown_methods()?;
+        let describe = |u: &UnknownMethod| {
+            let file = u.file.strip_prefix(&self.project.root).unwrap_or(&u.file);
+            let location = format!("{}:{}", file.display(), u.line);
+            match &u.suggestion {
+                Some(suggestion) => format!(
+                    "{}:{} ({}, did you mean '{}'?)",
+                    u.class, u.method, location, suggestion
+                ),
+                None => format!("{}:{} ({})", u.class, u.method, location),
+            }
+        };
+
+        match level {
+            CheckLevel::Error if !unknown.is_empty() => bail!(
+                "Unknown game methods: {}",
+                unknown.iter().map(describe).collect::<Vec<_>>().join(", ")
+            ),
+            _ => {
+                for method in &unknown {
+                    eprintln!("Warning: Unknown game method {}", describe(method));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::HookCatalogue;
+    use pkg::OdrillProject;
+
+    fn compiler(options: &str) -> Compiler {
+        let manifest = toml::from_str(&format!(
+            r#"
+            [package]
+            name = "demo"
+            version = "0.1.0"
+            authors = []
+
+            [[hooks]]
+            id = "lib/managers/somethingnew"
+            entry = "src/main.lua"
+            output = "hooks/main.lua"
+
+            [options]
+            {}
+            "#,
+            options
+        ))
+        .unwrap();
+        Compiler::new(OdrillProject {
+            root: PathBuf::from("/virtual/demo"),
+            manifest,
+        })
+    }
+
+    #[test]
+    fn test_partial_catalogue() {
+        // The bundled catalogue only checks ids when asked to
+        assert!(compiler("").check_hook_ids().is_ok());
+        let error = compiler(r#"hook_ids = "error""#)
+            .check_hook_ids()
+            .unwrap_err();
+        assert!(error.to_string().contains("partial"));
+
+        let full = HookCatalogue::parse("lib/managers/menumanager\n");
+        assert!(
+            compiler("")
+                .with_hook_catalogue(full.clone())
+                .check_hook_ids()
+                .is_ok()
+        );
+        assert!(
+            compiler(r#"hook_ids = "error""#)
+                .with_hook_catalogue(full)
+                .check_hook_ids()
+                .is_err()
+        );
+    }
+}
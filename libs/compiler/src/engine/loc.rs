@@ -0,0 +1,95 @@
+use super::Compiler;
+use crate::localization::{self, LocReference};
+use anyhow::bail;
+use pkg::manifest::CheckLevel;
+use std::collections::BTreeSet;
+
+impl Compiler {
+    /// Localization keys used by the hooks' and keybinds' sources, the menu
+    /// files in `assets` and the keybind names
+    pub fn loc_references(&self) -> anyhow::Result<Vec<LocReference>> {
+        let sources = self.sources();
+        let manifest = &self.project.manifest;
+        let files = self.entry_sources()?;
+
+        let mut references = Vec::new();
+        for file in &files {
+            let content = sources.read(file)?;
+            references.extend(self.parser.extract_loc_keys(&content).into_iter().map(|r| {
+                LocReference {
+                    key: r.key,
+                    file: file.clone(),
+                    line: r.line,
+                }
+            }));
+        }
+
+        let assets: Vec<_> = manifest
+            .assets
+            .iter()
+            .map(|a| self.project.root.join(a.entry().from))
+            .collect();
+        references.extend(localization::menu_references(&assets));
+
+        let keybind_keys: Vec<String> = manifest
+            .keybinds
+            .iter()
+            .filter(|k| k.localized)
+            .flat_map(|k| [k.name.clone(), k.description.clone()])
+            .filter(|key| !key.is_empty())
+            .collect();
+        references.extend(localization::manifest_references(
+            &self.project.root.join("odrill.toml"),
+            &keybind_keys,
+        ));
+
+        Ok(references)
+    }
+
+    /// References to keys missing from every default language file
+    pub fn unknown_loc_keys(&self) -> anyhow::Result<Vec<LocReference>> {
+        let mut known = BTreeSet::new();
+        for config in &self.project.manifest.localization {
+            let default = self
+                .project
+                .root
+                .join(&config.directory)
+                .join(&config.default);
+            known.extend(localization::load_strings(&default)?.into_keys());
+        }
+
+        Ok(self
+            .loc_references()?
+            .into_iter()
+            .filter(|r| !known.contains(&r.key))
+            .collect())
+    }
+
+    /// Report unknown localization keys as set by `[options] loc_keys`
+    pub fn check_loc_keys(&self) -> anyhow::Result<()> {
+        let manifest = &self.project.manifest;
+        if manifest.localization.is_empty() || manifest.options.loc_keys == CheckLevel::Off {
+            return Ok(());
+        }
+
+        let unknown = self.unknown_loc_keys()?;
+        let describe = |r: &LocReference| {
+            let file = r.file.strip_prefix(&self.project.root).unwrap_or(&r.file);
+            format!("'{}' ({}:{})", r.key, file.display(), r.line)
+        };
+
+        match manifest.options.loc_keys {
+            CheckLevel::Error if !unknown.is_empty() => bail!(
+                "Unknown localization keys: {}",
+                unknown.iter().map(describe).collect::<Vec<_>>().join(", ")
+            ),
+            _ => {
+                for reference in &unknown {
+                    eprintln!("Warning: Unknown localization key {}", describe(reference));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
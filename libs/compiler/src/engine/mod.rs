@@ -1,32 +1,156 @@
+mod bundle;
 mod compiler_result;
+pub(crate) mod embed;
+mod env;
 mod generate;
+mod hooks;
+mod loc;
 mod ops;
+mod preprocess;
 mod processor;
 mod symbols;
 mod verify;
 
+pub use bundle::{Bundle, Chunk, ChunkKind};
 pub use compiler_result::CompilerResult;
+pub use env::BuildEnv;
+pub use hooks::{UnknownHookId, UnknownMethod};
+pub use preprocess::Preprocessors;
+pub use processor::Sources;
 pub use symbols::SymbolTable;
 
+use crate::hooks::{ClassIndex, HookCatalogue};
 use crate::parser::LuaParser;
+use crate::passes::{Pass, StripComments};
+use crate::vfs::{DiskFs, FileSystem, OverlayFs};
 use pkg::OdrillProject;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 pub struct Compiler {
     pub(crate) project: OdrillProject,
     pub(crate) parser: LuaParser,
     pub(crate) symbols: SymbolTable,
+    pub(crate) env: BuildEnv,
+    pub(crate) preprocessors: Preprocessors,
+    pub(crate) passes: Vec<Box<dyn Pass>>,
+    /// Where sources, embeds and packages are read from
+    pub(crate) source_fs: Arc<dyn FileSystem>,
+    /// Where bundles, generated modules and caches are written to
+    pub(crate) output_fs: Arc<dyn FileSystem>,
+    /// `output_fs` over `source_fs`: what bundling reads from
+    pub(crate) read_fs: Arc<dyn FileSystem>,
+    /// Game scripts hook ids are checked against
+    pub(crate) hook_catalogue: HookCatalogue,
+    /// Game classes hooked methods are checked against, from `odrill hooks index`
+    pub(crate) class_index: Option<ClassIndex>,
+    /// Package name → local project root used instead of `target/pkg/<name>`
+    pub(crate) packages: BTreeMap<String, PathBuf>,
 }
 
 impl Compiler {
     pub fn new(project: OdrillProject) -> Self {
         // Use manifest options or default?
         // let include = project.manifest.options.include_directive...;
-        let parser = LuaParser::new("--"); // Default Lua comment
+        let mut parser = LuaParser::new("--"); // Default Lua comment
+        let env = BuildEnv::from_manifest(&project, "dev");
+
+        let preprocessors =
+            Preprocessors::new(project.manifest.preprocessors.clone(), &project.root);
+        for ext in preprocessors.extensions() {
+            parser.add_source_extension(ext);
+        }
+
+        // Built-in passes enabled from [options]
+        let mut passes: Vec<Box<dyn Pass>> = Vec::new();
+        if project.manifest.options.strip_comments {
+            passes.push(Box::new(StripComments));
+        }
 
         Self {
             project,
             parser,
             symbols: SymbolTable::new(),
+            env,
+            preprocessors,
+            passes,
+            source_fs: Arc::new(DiskFs),
+            output_fs: Arc::new(DiskFs),
+            read_fs: Arc::new(DiskFs),
+            hook_catalogue: HookCatalogue::bundled(),
+            class_index: None,
+            packages: BTreeMap::new(),
+        }
+    }
+
+    pub fn project(&self) -> &OdrillProject {
+        &self.project
+    }
+
+    /// Register a transform pass, run after the already registered ones
+    pub fn add_pass(&mut self, pass: impl Pass + 'static) {
+        self.passes.push(Box::new(pass));
+    }
+
+    /// Builder form of [`Compiler::add_pass`]
+    pub fn with_pass(mut self, pass: impl Pass + 'static) -> Self {
+        self.add_pass(pass);
+        self
+    }
+
+    /// Override the build constants injected as `odrill_env`
+    pub fn with_env(mut self, env: BuildEnv) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Read sources from `fs` instead of the disk
+    pub fn with_source(mut self, fs: impl FileSystem + 'static) -> Self {
+        self.source_fs = Arc::new(fs);
+        self.update_read_fs();
+        self
+    }
+
+    /// Write bundles to `fs` instead of the disk
+    pub fn with_output(mut self, fs: impl FileSystem + 'static) -> Self {
+        self.output_fs = Arc::new(fs);
+        self.update_read_fs();
+        self
+    }
+
+    fn update_read_fs(&mut self) {
+        self.read_fs = Arc::new(OverlayFs::new(
+            self.output_fs.clone(),
+            self.source_fs.clone(),
+        ));
+    }
+
+    /// Check hook ids against `catalogue` instead of the bundled one
+    pub fn with_hook_catalogue(mut self, catalogue: HookCatalogue) -> Self {
+        self.hook_catalogue = catalogue;
+        self
+    }
+
+    /// Check hooked game methods against `index`
+    pub fn with_class_index(mut self, index: ClassIndex) -> Self {
+        self.class_index = Some(index);
+        self
+    }
+
+    /// Resolve `use <name>::...` to the local project at `root` instead of
+    /// the installed package
+    pub fn with_package(mut self, name: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        self.packages.insert(name.into(), root.into());
+        self
+    }
+
+    pub(crate) fn sources(&self) -> Sources<'_> {
+        Sources {
+            parser: &self.parser,
+            preprocessors: &self.preprocessors,
+            fs: self.read_fs.as_ref(),
+            packages: &self.packages,
         }
     }
 }
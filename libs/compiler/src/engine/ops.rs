@@ -1,11 +1,33 @@
 use super::Compiler;
 use super::compiler_result::CompilerResult;
 use crate::engine::processor;
-use pkg::manifest::HookConfig;
+use crate::error::BundlerError;
+use crate::settings::{self, Settings};
+use anyhow::Context;
+use pkg::manifest::{HookConfig, HookKind, OdrillManifest};
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Caches under target/: preprocessor outputs and converted textures
+const CACHE_DIRS: [&str; 2] = ["preprocess", "textures"];
 
 impl Compiler {
+    /// Delete the on-disk build caches so everything is converted again
+    pub fn clear_caches(&self) -> anyhow::Result<()> {
+        for dir in CACHE_DIRS {
+            let path = self.project.root.join("target").join(dir);
+            if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+                    .with_context(|| format!("Failed to clear {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn compile_all(&mut self) -> anyhow::Result<Vec<CompilerResult>> {
+        validate_hooks(&self.project.manifest)?;
+        self.write_generated_modules()?;
+
         let mut results = Vec::new();
 
         for hook in &self.project.manifest.hooks.clone() {
@@ -13,36 +35,69 @@ impl Compiler {
             results.push(result);
         }
 
+        for keybind in &self.project.manifest.keybinds.clone() {
+            let result = self.compile_entry(&keybind.entry, &keybind.output)?;
+            results.push(result);
+        }
+
         self.symbols.warn_conflicts();
 
         Ok(results)
     }
 
-    pub fn compile_hook(&mut self, hook: &HookConfig) -> anyhow::Result<CompilerResult> {
-        let entry_path = self.project.root.join(&hook.entry);
-        let output_path = self.project.root.join("dist").join(&hook.output);
-        let src_root = self.project.root.join("src");
+    /// Write the `odrill::` modules generated from the manifest
+    pub(crate) fn write_generated_modules(&self) -> anyhow::Result<()> {
+        if let Some(settings) = Settings::new(&self.project.manifest)? {
+            let path = settings::module_path(&self.project.root);
+            if let Some(parent) = path.parent() {
+                self.output_fs.create_dir_all(parent)?;
+            }
+            self.output_fs
+                .write(&path, settings.module_lua().as_bytes())
+                .map_err(|e| BundlerError::FileWrite { path, source: e })?;
+        }
+        Ok(())
+    }
 
+    /// Source and embedded data files a script is bundled from, canonicalized
+    pub fn entry_dependencies(&self, entry: &Path) -> anyhow::Result<Vec<PathBuf>> {
         let mut visited = HashSet::new();
         let mut source_files = Vec::new();
 
         processor::collect_dependencies(
-            &entry_path,
-            &src_root,
-            &self.parser,
+            &self.project.root.join(entry),
+            &self.project.root.join("src"),
+            &self.sources(),
             &mut visited,
             &mut source_files,
         )?;
 
+        Ok(source_files)
+    }
+
+    pub fn compile_hook(&mut self, hook: &HookConfig) -> anyhow::Result<CompilerResult> {
+        self.compile_entry(&hook.entry, &hook.output)
+    }
+
+    /// Bundle a script (hook or keybind handler) into dist/
+    pub fn compile_entry(&mut self, entry: &Path, output: &Path) -> anyhow::Result<CompilerResult> {
+        let entry_path = self.project.root.join(entry);
+        let output_path = self.project.root.join("dist").join(output);
+        let src_root = self.project.root.join("src");
+
+        let source_files = self.entry_dependencies(entry)?;
+
         let bundled = self.generate_bundle(&entry_path, &src_root)?;
-        // apply options? project.manifest.options...
-        // let bundled = self.apply_options(bundled); // Need to check if apply_options exists
-        // generate_bundle is in `generate.rs`. I need to update `generate.rs` too.
 
         if let Some(parent) = output_path.parent() {
-            std::fs::create_dir_all(parent)?;
+            self.output_fs.create_dir_all(parent)?;
         }
-        std::fs::write(&output_path, &bundled)?;
+        self.output_fs
+            .write(&output_path, bundled.as_bytes())
+            .map_err(|e| crate::error::BundlerError::FileWrite {
+                path: output_path.clone(),
+                source: e,
+            })?;
 
         Ok(CompilerResult {
             output_path,
@@ -52,3 +107,51 @@ impl Compiler {
         })
     }
 }
+
+/// Check kind-specific hook fields and keybind ids. Hooks may share a game
+/// script id but each hook and keybind handler needs its own output file.
+pub(crate) fn validate_hooks(manifest: &OdrillManifest) -> Result<(), BundlerError> {
+    let invalid = |message: String| BundlerError::InvalidConfig { message };
+    let mut outputs = HashSet::new();
+
+    for hook in &manifest.hooks {
+        let entry = hook.entry.display();
+        match hook.kind {
+            HookKind::Post | HookKind::Pre if hook.id.is_empty() => {
+                return Err(invalid(format!("hook {} needs an `id`", entry)));
+            }
+            HookKind::Persist if hook.global.as_deref().unwrap_or("").is_empty() => {
+                return Err(invalid(format!("persist hook {} needs a `global`", entry)));
+            }
+            _ => {}
+        }
+
+        if !outputs.insert(&hook.output) {
+            return Err(invalid(format!(
+                "several hooks write to {}",
+                hook.output.display()
+            )));
+        }
+    }
+
+    let mut ids = HashSet::new();
+    for keybind in &manifest.keybinds {
+        if keybind.id.is_empty() {
+            return Err(invalid(format!(
+                "keybind {} needs an `id`",
+                keybind.entry.display()
+            )));
+        }
+        if !ids.insert(&keybind.id) {
+            return Err(invalid(format!("duplicate keybind id '{}'", keybind.id)));
+        }
+        if !outputs.insert(&keybind.output) {
+            return Err(invalid(format!(
+                "several hooks write to {}",
+                keybind.output.display()
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+//! Preprocessor pipeline: turns non-Lua sources (Teal, Fennel, ...) into Lua
+
+use crate::error::BundlerError;
+use crate::vfs::FileSystem;
+use pkg::manifest::PreprocessorConfig;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Reads sources, running the configured preprocessor for non-Lua files.
+/// Outputs are cached in `target/preprocess/` by content hash.
+#[derive(Debug, Default)]
+pub struct Preprocessors {
+    configs: Vec<PreprocessorConfig>,
+    root: PathBuf,
+}
+
+impl Preprocessors {
+    pub fn new(configs: Vec<PreprocessorConfig>, root: &Path) -> Self {
+        Self {
+            configs,
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// All extensions handled by a preprocessor
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.configs
+            .iter()
+            .flat_map(|c| c.extensions.iter().map(|e| e.as_str()))
+    }
+
+    /// Read a source file as Lua
+    pub fn read_source(&self, file: &Path, fs: &dyn FileSystem) -> anyhow::Result<String> {
+        let content = fs
+            .read_to_string(file)
+            .map_err(|e| BundlerError::FileRead {
+                path: file.to_path_buf(),
+                source: e,
+            })?;
+
+        let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let Some(config) = self
+            .configs
+            .iter()
+            .find(|c| c.extensions.iter().any(|e| e == ext))
+        else {
+            return Ok(content);
+        };
+
+        // The path is part of the key for commands whose output depends on it
+        let relative = file.strip_prefix(&self.root).unwrap_or(file);
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(b"\0");
+        hasher.update(config.command.as_bytes());
+        for arg in &config.args {
+            hasher.update(b"\0");
+            hasher.update(arg.as_bytes());
+        }
+        hasher.update(b"\0");
+        hasher.update(content.as_bytes());
+        let cache_path = self
+            .root
+            .join("target")
+            .join("preprocess")
+            .join(format!("{}.lua", hasher.finalize().to_hex()));
+
+        if let Ok(cached) = fs.read_to_string(&cache_path) {
+            return Ok(cached);
+        }
+
+        let lua = self.run(config, file, &content, fs)?;
+
+        if let Some(parent) = cache_path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        fs.write(&cache_path, lua.as_bytes())
+            .map_err(|e| BundlerError::FileWrite {
+                path: cache_path.clone(),
+                source: e,
+            })?;
+
+        Ok(lua)
+    }
+
+    fn run(
+        &self,
+        config: &PreprocessorConfig,
+        file: &Path,
+        content: &str,
+        fs: &dyn FileSystem,
+    ) -> anyhow::Result<String> {
+        let uses_stdin = !config.args.iter().any(|a| a.contains("{input}"));
+
+        // Commands taking a path need the source on disk
+        let temp_input = match fs.real_path(file) {
+            None if !uses_stdin => {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                let path =
+                    std::env::temp_dir().join(format!("odrill-{}-{}", std::process::id(), name));
+                std::fs::write(&path, content)?;
+                Some(path)
+            }
+            _ => None,
+        };
+        let input = temp_input
+            .clone()
+            .or_else(|| fs.real_path(file))
+            .unwrap_or_else(|| file.to_path_buf());
+
+        let result = self.spawn(config, file, &input, content, uses_stdin);
+
+        if let Some(path) = temp_input {
+            let _ = std::fs::remove_file(path);
+        }
+
+        result
+    }
+
+    fn spawn(
+        &self,
+        config: &PreprocessorConfig,
+        file: &Path,
+        input: &Path,
+        content: &str,
+        uses_stdin: bool,
+    ) -> anyhow::Result<String> {
+        let input = input.display().to_string();
+        let args: Vec<String> = config
+            .args
+            .iter()
+            .map(|a| a.replace("{input}", &input))
+            .collect();
+
+        let failed = |message: String| BundlerError::Preprocess {
+            file: file.to_path_buf(),
+            command: config.command.clone(),
+            message,
+        };
+
+        let mut command = Command::new(&config.command);
+        if self.root.is_dir() {
+            command.current_dir(&self.root);
+        }
+
+        let mut child = command
+            .args(&args)
+            .stdin(if uses_stdin {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(e.to_string()))?;
+
+        // Feed stdin from another thread: writing it all before reading stdout
+        // deadlocks once the child fills its output pipe
+        let writer = child.stdin.take().map(|mut stdin| {
+            let content = content.to_string();
+            std::thread::spawn(move || stdin.write_all(content.as_bytes()))
+        });
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| failed(e.to_string()))?;
+        let written = writer.map(|w| w.join().unwrap_or(Ok(())));
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(failed(if stderr.is_empty() {
+                format!("exited with {}", output.status)
+            } else {
+                stderr
+            })
+            .into());
+        }
+
+        if let Some(Err(e)) = written {
+            return Err(failed(e.to_string()).into());
+        }
+
+        String::from_utf8(output.stdout)
+            .map_err(|_| failed("output is not valid UTF-8".into()).into())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFs;
+
+    fn preprocessors(command: &str, args: &[&str]) -> Preprocessors {
+        Preprocessors::new(
+            vec![PreprocessorConfig {
+                extensions: vec!["up".to_string()],
+                command: command.to_string(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+            }],
+            Path::new("/project"),
+        )
+    }
+
+    #[test]
+    fn test_preprocess_stdin() {
+        let fs = MemoryFs::new();
+        // Larger than a pipe buffer, so stdin and stdout must be handled together
+        let source = "print('hi')\n".repeat(20_000);
+        fs.insert("/project/src/a.up", source.clone());
+        fs.insert("/project/src/b.up", source.clone());
+
+        let pre = preprocessors("tr", &["a-z", "A-Z"]);
+        let lua = pre
+            .read_source(Path::new("/project/src/a.up"), &fs)
+            .unwrap();
+        assert_eq!(lua, source.to_uppercase());
+
+        // Same content, different path: cached separately
+        pre.read_source(Path::new("/project/src/b.up"), &fs)
+            .unwrap();
+        let cached = fs
+            .paths()
+            .into_iter()
+            .filter(|p| p.starts_with("/project/target/preprocess"))
+            .count();
+        assert_eq!(cached, 2);
+    }
+
+    #[test]
+    fn test_preprocess_failure() {
+        let fs = MemoryFs::new();
+        fs.insert("/project/src/a.up", "x");
+
+        let pre = preprocessors("sh", &["-c", "echo broken >&2; exit 3"]);
+        let err = pre
+            .read_source(Path::new("/project/src/a.up"), &fs)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("broken"), "{}", err);
+    }
+}
@@ -1,23 +1,41 @@
 //! File processing utilities for bundler
 
+use crate::engine::bundle::Bundle;
+use crate::engine::embed;
+use crate::engine::preprocess::Preprocessors;
 use crate::engine::symbols::SymbolTable;
 use crate::error::BundlerError;
 use crate::parser::{LuaParser, UseDirective};
-// parser/mod.rs exports ModuleInclude.
-// processor.rs might not use the type name explicitly?
-// Just use ModuleInclude.
-use std::collections::HashSet;
+use crate::vfs::FileSystem;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 
+/// Everything needed to locate and read project sources
+pub struct Sources<'a> {
+    pub parser: &'a LuaParser,
+    pub preprocessors: &'a Preprocessors,
+    pub fs: &'a dyn FileSystem,
+    /// Local projects used instead of packages in `target/pkg/`
+    pub packages: &'a BTreeMap<String, PathBuf>,
+}
+
+impl Sources<'_> {
+    /// Read a source file as Lua (running its preprocessor if any)
+    pub fn read(&self, file: &Path) -> anyhow::Result<String> {
+        self.preprocessors.read_source(file, self.fs)
+    }
+}
+
 /// Collect all dependencies recursively
 pub fn collect_dependencies(
     file: &Path,
     src_root: &Path,
-    parser: &LuaParser,
+    sources: &Sources,
     visited: &mut HashSet<PathBuf>,
     files: &mut Vec<PathBuf>,
 ) -> anyhow::Result<()> {
-    let canonical = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let parser = sources.parser;
+    let canonical = sources.fs.canonicalize(file);
 
     if visited.contains(&canonical) {
         return Ok(());
@@ -25,92 +43,161 @@ pub fn collect_dependencies(
     visited.insert(canonical.clone());
     files.push(canonical.clone());
 
-    let content = std::fs::read_to_string(file).map_err(|e| BundlerError::FileRead {
-        path: file.to_path_buf(),
-        source: e,
-    })?;
+    let content = sources.read(file)?;
+
+    // Embedded data files are dependencies too
+    let project_root = src_root.parent().unwrap_or(src_root);
+    for embed in parser.extract_embeds(&content) {
+        let resolved = parser
+            .resolve_embed_path(&embed.file_path, file, project_root, sources.fs)
+            .ok_or_else(|| BundlerError::EmbedNotFound {
+                path: embed.file_path.clone(),
+                from: file.to_path_buf(),
+            })?;
+        let canonical = sources.fs.canonicalize(&resolved);
+        if visited.insert(canonical.clone()) {
+            files.push(canonical);
+        }
+    }
 
     for inc in parser.extract_includes(&content) {
-        if let Some(resolved) = parser.resolve_module_path(&inc.module_path, file, src_root) {
-            collect_dependencies(&resolved, src_root, parser, visited, files)?;
+        if let Some(resolved) =
+            parser.resolve_module_path(&inc.module_path, file, src_root, sources.fs)
+        {
+            collect_dependencies(&resolved, src_root, sources, visited, files)?;
+        }
+    }
+
+    // Modules imported with `use` (unresolved ones are reported when bundling)
+    for use_dir in parser.extract_uses(&content) {
+        if let Ok(module) = resolve_use(&use_dir, src_root, sources) {
+            collect_dependencies(&module, src_root, sources, visited, files)?;
         }
     }
 
     Ok(())
 }
 
+/// Replace `odrill.embed("...")` calls with the Lua table literal of the data file
+pub fn expand_embeds(
+    content: &str,
+    file: &Path,
+    project_root: &Path,
+    sources: &Sources,
+) -> anyhow::Result<String> {
+    let parser = sources.parser;
+    let embeds = parser.extract_embeds(content);
+    if embeds.is_empty() {
+        return Ok(content.to_string());
+    }
+
+    // Splice from the end so earlier spans stay valid
+    let mut expanded = content.to_string();
+    for embed in embeds.iter().rev() {
+        let resolved = parser
+            .resolve_embed_path(&embed.file_path, file, project_root, sources.fs)
+            .ok_or_else(|| BundlerError::EmbedNotFound {
+                path: embed.file_path.clone(),
+                from: file.to_path_buf(),
+            })?;
+        let literal = embed::load_embed(&resolved, sources.fs)?;
+        expanded.replace_range(embed.span.clone(), &literal);
+    }
+
+    Ok(expanded)
+}
+
+/// File a `use` directive imports from: `odrill::` generated modules,
+/// `mods::` local modules, then packages in `target/pkg/` and local modules
+pub fn resolve_use(
+    use_dir: &UseDirective,
+    src_root: &Path,
+    sources: &Sources,
+) -> Result<PathBuf, String> {
+    let parser = sources.parser;
+    let project_root = src_root.parent().unwrap_or(src_root);
+
+    // Check for mods:: prefix (local module)
+    let (is_local_mod, clean_path) =
+        if let Some(stripped) = use_dir.module_path.strip_prefix("mods::") {
+            (true, stripped)
+        } else {
+            (false, use_dir.module_path.as_str())
+        };
+
+    // Convert Rust-style path (hud::colors) to filesystem path (hud/colors)
+    let fs_path = clean_path.replace("::", "/");
+
+    if let Some(generated) = clean_path.strip_prefix("odrill::") {
+        // Code generated from odrill.toml, e.g. odrill::settings
+        let file = project_root
+            .join("target")
+            .join("odrill")
+            .join(generated.replace("::", "/"))
+            .with_extension("lua");
+        if !sources.fs.is_file(&file) {
+            return Err(format!(
+                "Generated module not found: {}",
+                use_dir.module_path
+            ));
+        }
+        Ok(file)
+    } else if is_local_mod {
+        // Local module: only check src/ directory
+        parser
+            .find_module_file(&src_root.join(&fs_path), sources.fs)
+            .ok_or_else(|| format!("Local module not found: mods::{}", clean_path))
+    } else {
+        // External package: check target/pkg/ (or the local project standing
+        // in for it) first, then fallback to src/
+        let first_segment = clean_path.split("::").next().unwrap_or(clean_path);
+        let pkg_root = match sources.packages.get(first_segment) {
+            Some(root) => root.clone(),
+            None => project_root.join("target").join("pkg").join(first_segment),
+        };
+        let pkg_file = pkg_root.join("src").join("init.lua");
+
+        if sources.fs.is_file(&pkg_file) {
+            Ok(pkg_file)
+        } else {
+            parser
+                .find_module_file(&src_root.join(&fs_path), sources.fs)
+                .ok_or_else(|| format!("Module not found: {}", use_dir.module_path))
+        }
+    }
+}
+
 /// Process use directives and extract only requested symbols
 pub fn process_use_directives(
     uses: &[UseDirective],
     src_root: &Path,
-    parser: &LuaParser,
+    sources: &Sources,
     symbols: &mut SymbolTable,
-    output: &mut String,
+    bundle: &mut Bundle,
     visited_modules: &mut HashSet<PathBuf>,
 ) -> anyhow::Result<()> {
+    let parser = sources.parser;
     let project_root = src_root.parent().unwrap_or(src_root);
 
     for use_dir in uses {
-        // Check for mods:: prefix (local module)
-        let (is_local_mod, clean_path) =
-            if let Some(stripped) = use_dir.module_path.strip_prefix("mods::") {
-                (true, stripped)
-            } else {
-                (false, use_dir.module_path.as_str())
-            };
-
-        // Convert Rust-style path (hud::colors) to filesystem path (hud/colors)
-        let fs_path = clean_path.replace("::", "/");
-
-        let module_file = if is_local_mod {
-            // Local module: only check src/ directory
-            let local_file = src_root.join(&fs_path).with_extension("lua");
-            let local_mod_file = src_root.join(&fs_path).join("mod.lua");
-
-            if local_file.exists() {
-                local_file
-            } else if local_mod_file.exists() {
-                local_mod_file
-            } else {
-                eprintln!("⚠️  Local module not found: mods::{}", clean_path);
-                continue;
-            }
-        } else {
-            // External package: check target/pkg/ first, then fallback to src/
-            let first_segment = clean_path.split("::").next().unwrap_or(clean_path);
-            let pkg_file = project_root
-                .join("target")
-                .join("pkg")
-                .join(first_segment)
-                .join("src")
-                .join("init.lua");
-
-            let local_file = src_root.join(&fs_path).with_extension("lua");
-            let local_mod_file = src_root.join(&fs_path).join("mod.lua");
-
-            if pkg_file.exists() {
-                pkg_file
-            } else if local_file.exists() {
-                local_file
-            } else if local_mod_file.exists() {
-                local_mod_file
-            } else {
-                eprintln!("⚠️  Module not found: {}", use_dir.module_path);
+        let module_file = match resolve_use(use_dir, src_root, sources) {
+            Ok(file) => file,
+            Err(message) => {
+                eprintln!("⚠️  {}", message);
                 continue;
             }
         };
 
         // Read module content
-        let content = std::fs::read_to_string(&module_file)?;
+        let content = sources.read(&module_file)?;
+        let content = expand_embeds(&content, &module_file, project_root, sources)?;
         let file_symbols = parser.build_symbol_table(&content);
 
         // Process module-level dependencies first (transitive imports)
         let module_uses = parser.extract_uses(&content);
         if !module_uses.is_empty() {
             // Check if we already processed this module's imports to avoid cycles
-            let canonical_mod = module_file
-                .canonicalize()
-                .unwrap_or_else(|_| module_file.clone());
+            let canonical_mod = sources.fs.canonicalize(&module_file);
             if !visited_modules.contains(&canonical_mod) {
                 visited_modules.insert(canonical_mod.clone());
 
@@ -118,23 +205,25 @@ pub fn process_use_directives(
                 process_use_directives(
                     &module_uses,
                     src_root,
-                    parser,
+                    sources,
                     symbols,
-                    output,
+                    bundle,
                     visited_modules,
                 )?;
             }
         }
 
         if use_dir.symbols.is_empty() {
-            // Import all (::*)
-            for (name, def) in file_symbols.iter() {
+            // Import all (::*), in source order for deterministic output
+            let mut all: Vec<_> = file_symbols.iter().collect();
+            all.sort_by_key(|(_, def)| def.start_line);
+            for (name, def) in all {
                 include_symbol(
-                    &name,
+                    name,
                     &def.content,
                     &module_file,
                     symbols,
-                    output,
+                    bundle,
                     &file_symbols,
                 );
             }
@@ -147,7 +236,7 @@ pub fn process_use_directives(
                         &def.content,
                         &module_file,
                         symbols,
-                        output,
+                        bundle,
                         &file_symbols,
                     );
                 } else {
@@ -167,7 +256,7 @@ fn include_symbol(
     content: &str,
     file: &Path,
     symbols: &mut SymbolTable,
-    output: &mut String,
+    bundle: &mut Bundle,
     all_file_symbols: &std::collections::HashMap<String, crate::parser::FunctionDef>,
 ) {
     if symbols.contains(name) {
@@ -182,7 +271,7 @@ fn include_symbol(
         content: content.to_string(),
         is_local: false,
     };
-    symbols.register(name, &file.to_path_buf(), def);
+    symbols.register(name, file, def);
 
     // Detect dependencies: find all uppercase identifiers used in this symbol
     // that might be local constants/tables from the same file
@@ -196,15 +285,13 @@ fn include_symbol(
                 &dep_def.content,
                 file,
                 symbols,
-                output,
+                bundle,
                 all_file_symbols,
             );
         }
     }
 
-    output.push_str(&format!("-- [use {}::{}]\n", file.display(), name));
-    output.push_str(content);
-    output.push('\n');
+    bundle.push_symbol(name, file, content.to_string());
 }
 
 /// Extract dependencies from symbol content
@@ -227,5 +314,8 @@ fn extract_dependencies(
         }
     }
 
-    deps.into_iter().collect()
+    // Source order keeps the output deterministic
+    let mut deps: Vec<String> = deps.into_iter().collect();
+    deps.sort_by_key(|name| all_symbols[name].start_line);
+    deps
 }
@@ -2,7 +2,7 @@
 
 use crate::parser::FunctionDef;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Tracks symbols that have been included globally to avoid duplication
 #[derive(Debug, Default)]
@@ -19,16 +19,17 @@ impl SymbolTable {
     }
 
     /// Register a symbol. Returns true if new, false if already exists.
-    pub fn register(&mut self, name: &str, file: &PathBuf, def: FunctionDef) -> bool {
+    pub fn register(&mut self, name: &str, file: &Path, def: FunctionDef) -> bool {
         if let Some((existing_file, _)) = self.symbols.get(name) {
             // Conflict detected
             self.conflicts
                 .entry(name.to_string())
                 .or_insert_with(|| vec![existing_file.clone()])
-                .push(file.clone());
+                .push(file.to_path_buf());
             false
         } else {
-            self.symbols.insert(name.to_string(), (file.clone(), def));
+            self.symbols
+                .insert(name.to_string(), (file.to_path_buf(), def));
             true
         }
     }
@@ -1,18 +1,63 @@
 use super::Compiler;
 use crate::engine::processor;
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 impl Compiler {
-    /// Verify all hooks without generating output
+    /// Verify all hooks and keybind handlers without generating output
     pub fn verify_all(&self) -> anyhow::Result<()> {
+        super::ops::validate_hooks(&self.project.manifest)?;
+        self.write_generated_modules()?;
         for hook in &self.project.manifest.hooks {
             self.verify_hook(hook)?;
         }
+        for keybind in &self.project.manifest.keybinds {
+            self.verify_file(&self.project.root.join(&keybind.entry))?;
+        }
         Ok(())
     }
 
     pub fn verify_hook(&self, hook: &pkg::manifest::HookConfig) -> anyhow::Result<()> {
-        let entry_path = self.project.root.join(&hook.entry);
+        self.verify_file(&self.project.root.join(&hook.entry))
+    }
+
+    /// Project source files reachable from the hook and keybind entries,
+    /// through includes and `use` imports
+    pub(crate) fn entry_sources(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let src_root = self.project.root.join("src");
+        let sources = self.sources();
+        let manifest = &self.project.manifest;
+
+        let mut visited = HashSet::new();
+        let mut files = Vec::new();
+        let entries = manifest
+            .hooks
+            .iter()
+            .map(|h| &h.entry)
+            .chain(manifest.keybinds.iter().map(|k| &k.entry));
+        for entry in entries {
+            processor::collect_dependencies(
+                &self.project.root.join(entry),
+                &src_root,
+                &sources,
+                &mut visited,
+                &mut files,
+            )?;
+        }
+
+        // Generated modules and packages under target/ aren't the project's sources
+        let target = sources.fs.canonicalize(&self.project.root.join("target"));
+        files.retain(|f| {
+            !f.starts_with(&target)
+                && f.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| self.parser.is_source_extension(e))
+        });
+        Ok(files)
+    }
+
+    /// Check that a source file and everything it includes can be read
+    pub fn verify_file(&self, entry_path: &Path) -> anyhow::Result<()> {
         let src_root = self.project.root.join("src");
 
         let mut visited = HashSet::new();
@@ -20,9 +65,9 @@ impl Compiler {
 
         // Just run collection to check for errors
         processor::collect_dependencies(
-            &entry_path,
+            entry_path,
             &src_root,
-            &self.parser,
+            &self.sources(),
             &mut visited,
             &mut source_files,
         )?;
@@ -28,6 +28,19 @@ pub enum BundlerError {
     #[error("Invalid config: {message}")]
     InvalidConfig { message: String },
 
+    #[error("Embed not found: {path} (referenced from {from})")]
+    EmbedNotFound { path: String, from: PathBuf },
+
+    #[error("Invalid embedded data in {file}: {message}")]
+    InvalidEmbed { file: PathBuf, message: String },
+
+    #[error("Preprocessor `{command}` failed on {file}: {message}")]
+    Preprocess {
+        file: PathBuf,
+        command: String,
+        message: String,
+    },
+
     #[error("Parse error in {file} at line {line}: {message}")]
     ParseError {
         file: PathBuf,
@@ -0,0 +1,223 @@
+//! Index of the game's classes and method signatures, built from decompiled sources
+
+use super::{distance, script_path};
+use crate::parser::LuaParser;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Class → method → signature
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassIndex {
+    pub classes: BTreeMap<String, ClassInfo>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassInfo {
+    /// Base class from `class(Base)`; its methods are inherited
+    pub parent: Option<String>,
+    pub methods: BTreeMap<String, MethodSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodSignature {
+    pub params: Vec<String>,
+    /// Defined with `.` (no implicit `self`)
+    #[serde(default)]
+    pub is_static: bool,
+    /// Game script defining it (`lib/managers/hudmanager`)
+    pub script: String,
+    pub line: usize,
+}
+
+impl MethodSignature {
+    /// `Class:method(a, b)`
+    pub fn display(&self, class: &str, method: &str) -> String {
+        let separator = if self.is_static { "." } else { ":" };
+        format!(
+            "{}{}{}({})",
+            class,
+            separator,
+            method,
+            self.params.join(", ")
+        )
+    }
+}
+
+impl ClassIndex {
+    /// Parse every `.lua` file of a decompiled game source tree
+    pub fn from_sources(dir: &Path, parser: &LuaParser) -> anyhow::Result<Self> {
+        let mut index = Self::default();
+
+        for entry in WalkDir::new(dir).sort_by_file_name().into_iter().flatten() {
+            let Some(script) = script_path(dir, entry.path()) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read(entry.path()) else {
+                continue;
+            };
+            let source = String::from_utf8_lossy(&source);
+
+            for class in parser.extract_classes(&source) {
+                let info = index.classes.entry(class.name).or_default();
+                if class.parent.is_some() {
+                    info.parent = class.parent;
+                }
+            }
+            for method in parser.extract_methods(&source) {
+                index
+                    .classes
+                    .entry(method.class)
+                    .or_default()
+                    .methods
+                    .insert(
+                        method.method,
+                        MethodSignature {
+                            params: method.params,
+                            is_static: method.is_static,
+                            script: script.clone(),
+                            line: method.line,
+                        },
+                    );
+            }
+        }
+
+        if index.classes.is_empty() {
+            anyhow::bail!("No classes found in {}", dir.display());
+        }
+        Ok(index)
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read class index {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse class index {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write class index {}", path.display()))
+    }
+
+    pub fn class(&self, name: &str) -> Option<&ClassInfo> {
+        self.classes.get(name)
+    }
+
+    /// Classes with methods defined in a game script, most methods first
+    pub fn classes_in(&self, script: &str) -> Vec<&str> {
+        let mut classes: Vec<(&str, usize)> = self
+            .classes
+            .iter()
+            .map(|(name, info)| {
+                let count = info.methods.values().filter(|m| m.script == script).count();
+                (name.as_str(), count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        classes.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        classes.into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// Look a method up on `class` and its base classes. Returns the class
+    /// defining it.
+    pub fn method(&self, class: &str, method: &str) -> Option<(&str, &MethodSignature)> {
+        self.ancestors(class)
+            .find_map(|(name, info)| Some((name, info.methods.get(method)?)))
+    }
+
+    /// Closest method name of `class` (inherited ones included)
+    pub fn suggest_method(&self, class: &str, method: &str) -> Option<&str> {
+        let max_distance = (method.len() / 5).max(2);
+        self.ancestors(class)
+            .flat_map(|(_, info)| info.methods.keys())
+            .map(|m| (distance(method, m), m))
+            .filter(|(d, _)| *d <= max_distance)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, m)| m.as_str())
+    }
+
+    /// Closest class name, ignoring case
+    pub fn suggest_class(&self, class: &str) -> Option<&str> {
+        let lower = class.to_lowercase();
+        self.classes
+            .keys()
+            .map(|c| (distance(&lower, &c.to_lowercase()), c))
+            .filter(|(d, _)| *d <= (class.len() / 5).max(2))
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, c)| c.as_str())
+    }
+
+    /// `class` then its base classes, stopping on unknown or cyclic parents
+    fn ancestors(&self, class: &str) -> impl Iterator<Item = (&str, &ClassInfo)> {
+        let mut next = self.classes.get_key_value(class);
+        let mut seen: Vec<&str> = Vec::new();
+        std::iter::from_fn(move || {
+            let (name, info) = next.take()?;
+            if seen.contains(&name.as_str()) {
+                return None;
+            }
+            seen.push(name);
+            next = info
+                .parent
+                .as_deref()
+                .and_then(|p| self.classes.get_key_value(p));
+            Some((name.as_str(), info))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_index() {
+        let dir = std::env::temp_dir().join(format!("odrill-classes-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("lua/lib/units")).unwrap();
+        std::fs::write(
+            dir.join("lua/lib/units/copdamage.lua"),
+            "CopDamage = CopDamage or class()\n\nfunction CopDamage:damage_bullet(attack_data)\nend\n\nfunction CopDamage.is_civilian(type)\nend\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("lua/lib/units/tankcopdamage.lua"),
+            "TankCopDamage = TankCopDamage or class(CopDamage)\nfunction TankCopDamage:init(unit, ...)\nend\n",
+        )
+        .unwrap();
+
+        let index = ClassIndex::from_sources(&dir, &LuaParser::new("--")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (owner, signature) = index.method("TankCopDamage", "damage_bullet").unwrap();
+        assert_eq!(owner, "CopDamage");
+        assert_eq!(signature.script, "lib/units/copdamage");
+        assert_eq!(signature.line, 3);
+        assert_eq!(
+            signature.display(owner, "damage_bullet"),
+            "CopDamage:damage_bullet(attack_data)"
+        );
+        assert!(
+            index
+                .method("CopDamage", "is_civilian")
+                .unwrap()
+                .1
+                .is_static
+        );
+        assert_eq!(
+            index.suggest_method("TankCopDamage", "damage_bulet"),
+            Some("damage_bullet")
+        );
+        assert_eq!(index.suggest_class("copdamage"), Some("CopDamage"));
+        assert_eq!(
+            index.classes_in("lib/units/tankcopdamage"),
+            vec!["TankCopDamage"]
+        );
+    }
+}
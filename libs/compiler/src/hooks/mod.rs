@@ -0,0 +1,230 @@
+//! Catalogue of the game scripts hooks can target (`lib/managers/hudmanagerpd2`, ...)
+//! and index of the classes and methods they define
+
+mod index;
+
+pub use index::{ClassIndex, ClassInfo, MethodSignature};
+
+use anyhow::Context;
+use std::collections::BTreeSet;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Script paths shipped with odrill: the commonly hooked subset of PD2's sources
+const BUNDLED: &str = include_str!("scripts.txt");
+
+/// Appended to unknown hook id reports made against the bundled catalogue
+pub const PARTIAL_NOTE: &str =
+    "odrill's bundled catalogue is partial, `odrill hooks refresh <dir>` indexes every game script";
+
+/// Known game script paths, one per line in text form
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookCatalogue {
+    scripts: BTreeSet<String>,
+    partial: bool,
+}
+
+impl HookCatalogue {
+    /// The catalogue shipped with odrill
+    pub fn bundled() -> Self {
+        Self {
+            partial: true,
+            ..Self::parse(BUNDLED)
+        }
+    }
+
+    /// Parse one script path per line. Blank lines and `#` comments are skipped.
+    pub fn parse(content: &str) -> Self {
+        Self {
+            scripts: content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(str::to_string)
+                .collect(),
+            partial: false,
+        }
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read hook catalogue {}", path.display()))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Index the `.lua` files of a decompiled game source tree. Paths start at
+    /// their `lib/` or `core/` folder, wherever the dump puts it.
+    pub fn from_sources(dir: &Path) -> anyhow::Result<Self> {
+        if !dir.is_dir() {
+            anyhow::bail!("Game sources not found: {}", dir.display());
+        }
+
+        let scripts: BTreeSet<String> = WalkDir::new(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| script_path(dir, entry.path()))
+            .collect();
+
+        if scripts.is_empty() {
+            anyhow::bail!("No game scripts (lib/**/*.lua) in {}", dir.display());
+        }
+        Ok(Self {
+            scripts,
+            partial: false,
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text: String = self.scripts.iter().map(|s| format!("{}\n", s)).collect();
+        if text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_text())
+            .with_context(|| format!("Failed to write hook catalogue {}", path.display()))
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.scripts.contains(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Whether this is the bundled subset rather than every game script
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Scripts whose path contains every word of `term`, case-insensitively
+    pub fn search(&self, term: &str) -> Vec<&str> {
+        let words: Vec<String> = term.split_whitespace().map(str::to_lowercase).collect();
+        self.scripts
+            .iter()
+            .filter(|s| words.iter().all(|w| s.contains(w.as_str())))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Closest known script to a mistyped id: same file name in another
+    /// folder, else the smallest edit distance within a few characters
+    pub fn suggest(&self, id: &str) -> Option<&str> {
+        let id = id.trim().to_lowercase().replace('\\', "/");
+        let id = id.trim_end_matches(".lua");
+        if self.contains(id) {
+            return Some(self.scripts.get(id)?.as_str());
+        }
+
+        let name = id.rsplit('/').next().unwrap_or(id);
+        if let Some(script) = self
+            .scripts
+            .iter()
+            .find(|s| s.rsplit('/').next() == Some(name))
+        {
+            return Some(script);
+        }
+
+        // Bare names ("hudmanagrpd2") are compared with file names only
+        let bare = !id.contains('/');
+        let max_distance = (id.len() / 5).max(2);
+        self.scripts
+            .iter()
+            .map(|s| {
+                let target = if bare {
+                    s.rsplit('/').next().unwrap_or(s)
+                } else {
+                    s
+                };
+                (distance(id, target), s)
+            })
+            .filter(|(d, _)| *d <= max_distance)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, s)| s.as_str())
+    }
+}
+
+/// Game script path of a `.lua` file in a decompiled source tree: from its
+/// `lib/` or `core/` folder, without extension
+fn script_path(dir: &Path, path: &Path) -> Option<String> {
+    if !path.is_file() || path.extension().is_none_or(|e| e != "lua") {
+        return None;
+    }
+
+    let relative = path.with_extension("");
+    let parts: Vec<String> = relative
+        .strip_prefix(dir)
+        .ok()?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let start = parts.iter().position(|p| p == "lib" || p == "core")?;
+    Some(parts[start..].join("/"))
+}
+
+/// Levenshtein distance
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogue() {
+        let catalogue = HookCatalogue::bundled();
+        assert!(catalogue.contains("lib/managers/hudmanagerpd2"));
+        assert!(catalogue.contains("lib/managers/menumanager"));
+
+        assert_eq!(
+            catalogue.suggest("lib/managers/hudmanagerpd"),
+            Some("lib/managers/hudmanagerpd2")
+        );
+        assert_eq!(
+            catalogue.suggest("lib/managers/hudteammate"),
+            Some("lib/managers/hud/hudteammate")
+        );
+        assert_eq!(
+            catalogue.suggest("lib/managers/HUDManagerPD2.lua"),
+            Some("lib/managers/hudmanagerpd2")
+        );
+        assert_eq!(
+            catalogue.suggest("copdamge"),
+            Some("lib/units/enemies/cop/copdamage")
+        );
+        assert_eq!(catalogue.suggest("something/else/entirely"), None);
+
+        assert_eq!(
+            catalogue.search("copdamage husk"),
+            vec!["lib/units/enemies/cop/huskcopdamage"]
+        );
+        assert!(catalogue.is_partial());
+        let parsed = HookCatalogue::parse(&catalogue.to_text());
+        assert!(!parsed.is_partial());
+        assert_eq!(parsed.to_text(), catalogue.to_text());
+    }
+}
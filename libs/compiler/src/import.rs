@@ -0,0 +1,544 @@
+//! Convert an existing BLT/SuperBLT mod folder (mod.txt, main.xml and loose
+//! scripts) into an odrill project
+
+use anyhow::Context;
+use pkg::manifest::{
+    AssetConfig, BuildConfig, HookConfig, HookKind, KeybindConfig, LocalizationConfig,
+    OdrillManifest, OptionsConfig, PackageConfig, PackageKind, RunConfig, SuperBltConfig,
+    UpdateConfig,
+};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// mod.txt keys translated to odrill.toml
+const MOD_TXT_KEYS: &[&str] = &[
+    "name",
+    "description",
+    "author",
+    "contact",
+    "version",
+    "blt_version",
+    "color",
+    "image",
+    "priority",
+    "updates",
+    "hooks",
+    "pre_hooks",
+    "persist_scripts",
+    "keybinds",
+];
+
+/// A mod translated to an odrill project
+#[derive(Debug, Clone)]
+pub struct ImportedMod {
+    pub manifest: OdrillManifest,
+    pub files: Vec<ImportedFile>,
+    /// Things that couldn't be translated and need a manual look
+    pub notes: Vec<String>,
+}
+
+/// A file of the mod and where it goes in the project
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedFile {
+    pub src: PathBuf,
+    pub dest: PathBuf,
+}
+
+impl ImportedMod {
+    /// Read `mod_dir/mod.txt` (and `main.xml` if present)
+    pub fn read(mod_dir: &Path) -> anyhow::Result<Self> {
+        let mod_txt_path = mod_dir.join("mod.txt");
+        let content = std::fs::read_to_string(&mod_txt_path)
+            .with_context(|| format!("Failed to read {}", mod_txt_path.display()))?;
+        // BLT's parser is lenient: accept trailing commas and comments
+        let mod_txt: Value = json5::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", mod_txt_path.display()))?;
+        let Some(mod_txt) = mod_txt.as_object() else {
+            anyhow::bail!("{} is not a JSON object", mod_txt_path.display());
+        };
+
+        let mut import = Importer {
+            mod_dir,
+            files: Vec::new(),
+            scripts: HashMap::new(),
+            outputs: BTreeSet::new(),
+            notes: Vec::new(),
+        };
+
+        for key in mod_txt.keys() {
+            if !MOD_TXT_KEYS.contains(&key.as_str()) {
+                import.note(format!("mod.txt `{}` was not translated", key));
+            }
+        }
+
+        let mut hooks = Vec::new();
+        for (key, kind) in [("pre_hooks", HookKind::Pre), ("hooks", HookKind::Post)] {
+            for hook in objects(mod_txt, key) {
+                let id = string(hook, "hook_id").unwrap_or_default();
+                let (kind, id) = match id.as_str() {
+                    "*" => (HookKind::Wildcard, String::new()),
+                    _ => (kind, id),
+                };
+                if let Some((entry, output)) = import.script(hook, key) {
+                    hooks.push(HookConfig {
+                        kind,
+                        id,
+                        entry,
+                        output,
+                        priority: 0,
+                        global: None,
+                    });
+                }
+            }
+        }
+        for script in objects(mod_txt, "persist_scripts") {
+            if let Some((entry, output)) = import.script(script, "persist_scripts") {
+                hooks.push(HookConfig {
+                    kind: HookKind::Persist,
+                    id: String::new(),
+                    entry,
+                    output,
+                    priority: 0,
+                    global: string(script, "global"),
+                });
+            }
+        }
+
+        let mut keybinds = Vec::new();
+        for keybind in objects(mod_txt, "keybinds") {
+            if let Some((entry, output)) = import.script(keybind, "keybinds") {
+                keybinds.push(KeybindConfig {
+                    id: string(keybind, "keybind_id").unwrap_or_default(),
+                    name: string(keybind, "name").unwrap_or_default(),
+                    description: string(keybind, "description").unwrap_or_default(),
+                    key: None,
+                    entry,
+                    output,
+                    run_in_menu: boolean(keybind, "run_in_menu").unwrap_or(false),
+                    run_in_game: boolean(keybind, "run_in_game").unwrap_or(false),
+                    localized: boolean(keybind, "localized").unwrap_or(true),
+                });
+            }
+        }
+
+        let updates = objects(mod_txt, "updates")
+            .filter_map(|update| {
+                for key in update.keys() {
+                    if ![
+                        "identifier",
+                        "host",
+                        "display_name",
+                        "install_dir",
+                        "install_folder",
+                    ]
+                    .contains(&key.as_str())
+                    {
+                        import.note(format!("mod.txt update `{}` was not translated", key));
+                    }
+                }
+                Some(UpdateConfig {
+                    identifier: string(update, "identifier")?,
+                    host: update
+                        .get("host")
+                        .and_then(Value::as_object)
+                        .and_then(|host| string(host, "meta")),
+                    display_name: string(update, "display_name"),
+                    install_dir: string(update, "install_dir")
+                        .or_else(|| string(update, "install_folder")),
+                })
+            })
+            .collect();
+
+        let image = string(mod_txt, "image").map(PathBuf::from);
+        if let Some(image) = &image {
+            if mod_dir.join(image).is_file() {
+                import.claim(image);
+            } else {
+                import.note(format!("mod.txt image {} not found", image.display()));
+            }
+        }
+
+        let localization = match std::fs::read_to_string(mod_dir.join("main.xml")) {
+            Ok(xml) => import.main_xml(&xml)?,
+            Err(_) => Vec::new(),
+        };
+
+        let assets = import.remaining_assets();
+
+        let manifest = OdrillManifest {
+            package: PackageConfig {
+                name: string(mod_txt, "name").unwrap_or_else(|| dir_name(mod_dir)),
+                version: string(mod_txt, "version").unwrap_or_else(|| "0.1.0".to_string()),
+                description: string(mod_txt, "description").filter(|d| !d.is_empty()),
+                authors: string(mod_txt, "author").into_iter().collect(),
+                kind: PackageKind::Mod,
+            },
+            hooks,
+            options: OptionsConfig::default(),
+            dependencies: HashMap::new(),
+            assets,
+            localization,
+            preprocessors: Vec::new(),
+            superblt: SuperBltConfig {
+                contact: string(mod_txt, "contact"),
+                color: string(mod_txt, "color"),
+                image,
+                blt_version: string(mod_txt, "blt_version").and_then(|v| v.parse().ok()),
+                priority: string(mod_txt, "priority")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(0),
+                updates,
+            },
+            beardlib: None,
+            build: BuildConfig::default(),
+            settings: Vec::new(),
+            keybinds,
+            overrides: BTreeMap::new(),
+            textures: Vec::new(),
+            run: RunConfig::default(),
+        };
+
+        Ok(Self {
+            manifest,
+            files: import.files,
+            notes: import.notes,
+        })
+    }
+
+    /// Copy the mod's files into `project_dir`
+    pub fn copy_files(&self, project_dir: &Path) -> anyhow::Result<()> {
+        for file in &self.files {
+            let dest = project_dir.join(&file.dest);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&file.src, &dest)
+                .with_context(|| format!("Failed to copy {}", file.src.display()))?;
+        }
+        Ok(())
+    }
+}
+
+struct Importer<'a> {
+    mod_dir: &'a Path,
+    files: Vec<ImportedFile>,
+    /// Script path in mod.txt → entry in src/
+    scripts: HashMap<String, PathBuf>,
+    outputs: BTreeSet<PathBuf>,
+    notes: Vec<String>,
+}
+
+impl Importer<'_> {
+    fn note(&mut self, note: String) {
+        if !self.notes.contains(&note) {
+            self.notes.push(note);
+        }
+    }
+
+    /// Entry and output of the `script_path` of a mod.txt entry. Scripts move
+    /// to src/ (without their `lua/` prefix) and keep their path in dist/.
+    fn script(&mut self, object: &Map<String, Value>, section: &str) -> Option<(PathBuf, PathBuf)> {
+        let Some(script_path) = string(object, "script_path") else {
+            self.note(format!(
+                "mod.txt {} entry without script_path skipped",
+                section
+            ));
+            return None;
+        };
+        let script_path = script_path.trim_start_matches("./").replace('\\', "/");
+
+        let src = self.mod_dir.join(&script_path);
+        if !src.is_file() {
+            self.note(format!("{} ({}) not found, skipped", script_path, section));
+            return None;
+        }
+
+        let entry = match self.scripts.get(&script_path) {
+            Some(entry) => entry.clone(),
+            None => {
+                let relative = script_path.strip_prefix("lua/").unwrap_or(&script_path);
+                let mut entry = Path::new("src").join(relative);
+                if self.files.iter().any(|f| f.dest == entry) {
+                    entry = Path::new("src").join(&script_path);
+                }
+                self.files.push(ImportedFile {
+                    src,
+                    dest: entry.clone(),
+                });
+                self.scripts.insert(script_path.clone(), entry.clone());
+                entry
+            }
+        };
+
+        // Each hook writes its own bundle: a script hooked several times is
+        // bundled once per hook
+        let mut output = PathBuf::from(&script_path);
+        let mut n = 1;
+        while !self.outputs.insert(output.clone()) {
+            n += 1;
+            let stem = Path::new(&script_path).with_extension("");
+            output = PathBuf::from(format!("{}_{}.lua", stem.to_string_lossy(), n));
+        }
+        if n > 1 {
+            self.note(format!(
+                "{} is hooked several times; extra copies are written to {}",
+                script_path,
+                output.display()
+            ));
+        }
+
+        Some((entry, output))
+    }
+
+    /// Mark a mod file as copied to the same path in the project
+    fn claim(&mut self, path: &Path) {
+        self.files.push(ImportedFile {
+            src: self.mod_dir.join(path),
+            dest: path.to_path_buf(),
+        });
+    }
+
+    /// `[[localization]]` from the `<Localization>` modules of main.xml
+    fn main_xml(&mut self, xml: &str) -> anyhow::Result<Vec<LocalizationConfig>> {
+        let doc = roxmltree::Document::parse(xml).context("Failed to parse main.xml")?;
+
+        let mut localization = Vec::new();
+        for module in doc.root_element().children().filter(|n| n.is_element()) {
+            if module.tag_name().name() != "Localization" {
+                self.note(format!(
+                    "main.xml <{}> was not translated",
+                    module.tag_name().name()
+                ));
+                continue;
+            }
+
+            let directory = module.attribute("directory").unwrap_or("").to_string();
+            let Some(default) = module.attribute("default") else {
+                self.note("main.xml <Localization> without default skipped".to_string());
+                continue;
+            };
+
+            let languages: BTreeMap<String, String> = module
+                .children()
+                .filter(|n| n.has_tag_name("loc"))
+                .filter_map(|loc| {
+                    Some((
+                        loc.attribute("language")?.to_string(),
+                        loc.attribute("file")?.to_string(),
+                    ))
+                })
+                .collect();
+
+            let dir = self.mod_dir.join(&directory);
+            for file in walk(&dir) {
+                if let Ok(relative) = file.strip_prefix(self.mod_dir) {
+                    self.claim(relative);
+                }
+            }
+
+            localization.push(LocalizationConfig {
+                directory,
+                default: default.to_string(),
+                languages,
+            });
+        }
+
+        Ok(localization)
+    }
+
+    /// Copy every other file to the same path and list it in `assets`, by
+    /// top-level file or folder
+    fn remaining_assets(&mut self) -> Vec<AssetConfig> {
+        let claimed: BTreeSet<PathBuf> = self.files.iter().map(|f| f.src.clone()).collect();
+
+        let mut assets = BTreeSet::new();
+        let mut lua_files = 0;
+        for file in walk(self.mod_dir) {
+            let Ok(relative) = file.strip_prefix(self.mod_dir) else {
+                continue;
+            };
+            let relative = relative.to_path_buf();
+            if relative == Path::new("mod.txt")
+                || relative == Path::new("main.xml")
+                || claimed.contains(&file)
+            {
+                continue;
+            }
+
+            if relative.extension().is_some_and(|e| e == "lua") {
+                lua_files += 1;
+            }
+            if let Some(top) = relative.components().next() {
+                assets.insert(PathBuf::from(top.as_os_str()));
+            }
+            self.claim(&relative);
+        }
+
+        if lua_files > 0 {
+            self.note(format!(
+                "{} Lua file(s) not listed in mod.txt are copied as assets, unbundled",
+                lua_files
+            ));
+        }
+
+        assets.into_iter().map(AssetConfig::Path).collect()
+    }
+}
+
+/// Files under `dir`, without hidden ones (`.git`, ...)
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect()
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.canonicalize()
+        .ok()
+        .and_then(|d| d.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "imported-mod".to_string())
+}
+
+/// The objects of an array field
+fn objects<'a>(
+    object: &'a Map<String, Value>,
+    key: &str,
+) -> impl Iterator<Item = &'a Map<String, Value>> {
+    object
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+}
+
+/// A string field; mod.txt files often write numbers as strings and vice versa
+fn string(object: &Map<String, Value>, key: &str) -> Option<String> {
+    match object.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn boolean(object: &Map<String, Value>, key: &str) -> Option<bool> {
+    match object.get(key)? {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => Some(s == "true"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_mod() {
+        let dir = std::env::temp_dir().join(format!("odrill-import-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (path, content) in [
+            ("lua/menu.lua", "-- menu"),
+            ("lua/utils.lua", "-- dofile'd"),
+            ("persist.lua", "-- persist"),
+            ("loc/english.txt", "{}"),
+            ("icon.png", ""),
+        ] {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        std::fs::write(
+            dir.join("mod.txt"),
+            r#"{
+                "name": "Legacy",
+                "author": "Someone",
+                "version": 2,
+                "image": "icon.png",
+                "undisablable": true,
+                "hooks": [
+                    { "hook_id": "lib/managers/menumanager", "script_path": "lua/menu.lua" },
+                    { "hook_id": "lib/managers/hudmanager", "script_path": "lua/menu.lua" },
+                    { "hook_id": "*", "script_path": "lua/missing.lua" },
+                ],
+                "persist_scripts": [{ "global": "Loaded", "script_path": "persist.lua" }],
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("main.xml"),
+            r#"<mod><Localization directory="loc" default="english.txt"/><AddFiles/></mod>"#,
+        )
+        .unwrap();
+
+        let import = ImportedMod::read(&dir).unwrap();
+        let manifest = &import.manifest;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(manifest.package.version, "2");
+        assert_eq!(manifest.hooks.len(), 3);
+        assert_eq!(manifest.hooks[0].entry, Path::new("src/menu.lua"));
+        assert_eq!(manifest.hooks[1].output, Path::new("lua/menu_2.lua"));
+        assert_eq!(manifest.hooks[2].kind, HookKind::Persist);
+        assert_eq!(manifest.localization[0].directory, "loc");
+
+        let assets: Vec<_> = manifest.assets.iter().map(|a| a.entry().from).collect();
+        assert_eq!(assets, vec![PathBuf::from("lua")]);
+
+        let dests: Vec<_> = import.files.iter().map(|f| f.dest.clone()).collect();
+        assert!(dests.contains(&PathBuf::from("src/persist.lua")));
+        assert!(dests.contains(&PathBuf::from("lua/utils.lua")));
+        assert!(dests.contains(&PathBuf::from("loc/english.txt")));
+
+        let notes = import.notes.join("\n");
+        assert!(notes.contains("`undisablable`"));
+        assert!(notes.contains("lua/missing.lua"));
+        assert!(notes.contains("<AddFiles>"));
+    }
+
+    #[test]
+    fn test_import_then_build() {
+        let dir = std::env::temp_dir().join(format!("odrill-import-build-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mod_dir = dir.join("legacy");
+        for (path, content) in [
+            ("lua/menu.lua", "log(\"menu\")\n"),
+            ("strings/english.json", "{\"legacy_title\": \"Legacy\"}"),
+            (
+                "mod.txt",
+                r#"{ "name": "Legacy", "hooks": [{ "hook_id": "lib/managers/menumanager", "script_path": "lua/menu.lua" }] }"#,
+            ),
+            (
+                "main.xml",
+                r#"<mod><Localization directory="strings" default="english.json"/></mod>"#,
+            ),
+        ] {
+            let path = mod_dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let import = ImportedMod::read(&mod_dir).unwrap();
+        let root = dir.join("project");
+        import.copy_files(&root).unwrap();
+        let project = pkg::OdrillProject {
+            root: root.clone(),
+            manifest: import.manifest,
+        };
+        let dist = root.join("dist");
+        crate::Compiler::new(project.clone()).compile_all().unwrap();
+        let generated = crate::output::for_project(&project).generate(&project, &dist);
+        let copied = dist.join("strings/english.json").is_file();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        generated.unwrap();
+        assert!(copied);
+    }
+}
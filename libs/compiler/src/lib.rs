@@ -2,10 +2,22 @@
 //!
 //! Compiles Payday 2 mods from Odrill Projects.
 
+pub mod assets;
+pub mod beardlib;
 pub mod engine;
 pub mod error;
+pub mod hooks;
+pub mod import;
+pub mod localization;
+pub mod output;
+pub mod overrides;
 pub mod parser;
+pub mod passes;
+pub mod settings;
 pub mod superblt;
+pub mod vfs;
 
-pub use engine::Compiler;
+pub use engine::{BuildEnv, Compiler};
+pub use output::Target;
+pub use passes::{Pass, PassContext};
 // pub use engine::CompilerResult;
@@ -0,0 +1,256 @@
+//! Localization files: language discovery and validation
+
+mod pseudo;
+mod usage;
+
+pub use pseudo::{pseudo_localize, write_pseudo};
+pub use usage::{LocReference, append_keys, manifest_references, menu_references};
+
+use anyhow::{Context, bail};
+use pkg::manifest::LocalizationConfig;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// PD2 language names with the aliases accepted for them (file stems or codes)
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("english", &["en", "en-us", "en-gb"]),
+    ("french", &["fr", "fr-fr", "francais"]),
+    ("german", &["de", "de-de", "deutsch"]),
+    ("italian", &["it", "it-it", "italiano"]),
+    ("spanish", &["es", "es-es", "espanol"]),
+    ("russian", &["ru", "ru-ru"]),
+    ("dutch", &["nl", "nl-nl"]),
+    ("swedish", &["sv", "sv-se"]),
+    ("polish", &["pl", "pl-pl"]),
+    ("turkish", &["tr", "tr-tr"]),
+    ("portuguese", &["pt", "pt-br", "pt-pt", "brazilian"]),
+    ("czech", &["cs", "cs-cz"]),
+    ("japanese", &["ja", "ja-jp"]),
+    ("korean", &["ko", "ko-kr"]),
+    (
+        "schinese",
+        &["zh", "zh-cn", "chinese", "simplified_chinese"],
+    ),
+    ("tchinese", &["zh-tw", "traditional_chinese"]),
+];
+
+/// A localization file and the PD2 language it is loaded for
+#[derive(Debug, Clone, PartialEq)]
+pub struct Language {
+    pub language: String,
+    pub file: String,
+}
+
+/// PD2 language name for a language name, code or file stem
+pub fn language_code(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase().replace('_', "-");
+    LANGUAGES
+        .iter()
+        .find(|(code, aliases)| {
+            *code == name || aliases.iter().any(|a| a.replace('_', "-") == name)
+        })
+        .map(|(code, _)| *code)
+}
+
+/// Languages of a `[[localization]]` entry: declared ones, or the files found
+/// in its directory. The default file is always included.
+pub fn languages(config: &LocalizationConfig, project_root: &Path) -> Vec<Language> {
+    let mut found: BTreeMap<&'static str, String> = BTreeMap::new();
+
+    if config.languages.is_empty() {
+        let dir = project_root.join(&config.directory);
+        for file in loc_files(&dir) {
+            let stem = Path::new(&file)
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            match language_code(&stem) {
+                Some(code) => {
+                    found.entry(code).or_insert(file);
+                }
+                None => eprintln!(
+                    "Warning: Unknown language for {}, add it to [localization.languages]",
+                    dir.join(&file).display()
+                ),
+            }
+        }
+    } else {
+        for (name, file) in &config.languages {
+            match language_code(name) {
+                Some(code) => {
+                    found.insert(code, file.clone());
+                }
+                None => eprintln!("Warning: Unknown localization language '{}'", name),
+            }
+        }
+    }
+
+    let default_stem = Path::new(&config.default)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let default_code = language_code(&default_stem).unwrap_or("english");
+    found
+        .entry(default_code)
+        .or_insert_with(|| config.default.clone());
+
+    found
+        .into_iter()
+        .map(|(language, file)| Language {
+            language: language.to_string(),
+            file,
+        })
+        .collect()
+}
+
+/// Problems found in the localization files
+#[derive(Debug, Default)]
+pub struct LocReport {
+    /// (file, key) pairs: keys of the default file a translation lacks
+    pub missing: Vec<(String, String)>,
+    /// (file, key) pairs: keys of a translation absent from the default file
+    pub extra: Vec<(String, String)>,
+}
+
+impl LocReport {
+    pub fn print_warnings(&self) {
+        for (file, key) in &self.missing {
+            eprintln!("Warning: {} is missing key '{}'", file, key);
+        }
+        for (file, key) in &self.extra {
+            eprintln!(
+                "Warning: {} has key '{}' not in the default language",
+                file, key
+            );
+        }
+    }
+}
+
+/// Validate every `[[localization]]` entry, printing warnings for key mismatches
+pub fn check(configs: &[LocalizationConfig], project_root: &Path) -> anyhow::Result<()> {
+    for config in configs {
+        validate(config, project_root)?.print_warnings();
+    }
+    Ok(())
+}
+
+/// Compare every language against the default one. Malformed files are errors.
+pub fn validate(config: &LocalizationConfig, project_root: &Path) -> anyhow::Result<LocReport> {
+    let dir = project_root.join(&config.directory);
+    let default = load_strings(&dir.join(&config.default))?;
+    let mut report = LocReport::default();
+
+    for language in languages(config, project_root) {
+        if language.file == config.default {
+            continue;
+        }
+
+        let strings = load_strings(&dir.join(&language.file))?;
+        let file = format!("{}/{}", config.directory, language.file);
+
+        report.missing.extend(
+            default
+                .keys()
+                .filter(|k| !strings.contains_key(*k))
+                .map(|k| (file.clone(), k.clone())),
+        );
+        report.extra.extend(
+            strings
+                .keys()
+                .filter(|k| !default.contains_key(*k))
+                .map(|k| (file.clone(), k.clone())),
+        );
+    }
+
+    Ok(report)
+}
+
+/// Read a localization file: a flat JSON object of strings (`.json` or `.txt`)
+pub fn load_strings(path: &Path) -> anyhow::Result<BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Malformed localization file {}", path.display()))?;
+
+    let Some(object) = value.as_object() else {
+        bail!(
+            "Malformed localization file {}: expected an object of strings",
+            path.display()
+        );
+    };
+
+    object
+        .iter()
+        .map(|(key, value)| match value.as_str() {
+            Some(text) => Ok((key.clone(), text.to_string())),
+            None => bail!(
+                "Malformed localization file {}: '{}' is not a string",
+                path.display(),
+                key
+            ),
+        })
+        .collect()
+}
+
+/// `.json` and `.txt` files of a directory, sorted
+fn loc_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file() && matches!(p.extension().and_then(|e| e.to_str()), Some("json" | "txt"))
+        })
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_codes() {
+        assert_eq!(language_code("French"), Some("french"));
+        assert_eq!(language_code("pt_BR"), Some("portuguese"));
+        assert_eq!(language_code("zh-CN"), Some("schinese"));
+        assert_eq!(language_code("klingon"), None);
+    }
+
+    #[test]
+    fn test_validate_eok_loc() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/EOK");
+        let config = LocalizationConfig {
+            directory: "loc".to_string(),
+            default: "english.json".to_string(),
+            languages: BTreeMap::new(),
+        };
+
+        let languages = languages(&config, &root);
+        assert_eq!(
+            languages,
+            vec![
+                Language {
+                    language: "english".to_string(),
+                    file: "english.json".to_string(),
+                },
+                Language {
+                    language: "french".to_string(),
+                    file: "french.json".to_string(),
+                },
+            ]
+        );
+
+        let report = validate(&config, &root).unwrap();
+        assert!(report.missing.is_empty());
+        assert!(report.extra.is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! Pseudo-localization: makes every localized string recognizable in game,
+//! so hard-coded text stands out
+
+use super::{languages, load_strings};
+use pkg::manifest::LocalizationConfig;
+use std::path::Path;
+
+/// Accent letters and wrap the text in `[!! !!]`. PD2 macros (`$NAME;`) are kept.
+pub fn pseudo_localize(text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+
+    let mut output = String::from("[!! ");
+    let mut in_macro = false;
+
+    for c in text.chars() {
+        if c == '$' {
+            in_macro = true;
+        } else if in_macro && c == ';' {
+            in_macro = false;
+            output.push(c);
+            continue;
+        }
+
+        output.push(if in_macro { c } else { accent(c) });
+    }
+
+    output.push_str(" !!]");
+    output
+}
+
+fn accent(c: char) -> char {
+    match c {
+        'a' => 'á',
+        'e' => 'é',
+        'i' => 'í',
+        'o' => 'ö',
+        'u' => 'ü',
+        'c' => 'ç',
+        'n' => 'ñ',
+        'A' => 'Å',
+        'E' => 'É',
+        'I' => 'Î',
+        'O' => 'Ø',
+        'U' => 'Û',
+        'C' => 'Ç',
+        'N' => 'Ñ',
+        other => other,
+    }
+}
+
+/// Replace every language file in dist/ with the pseudo-localized default strings.
+/// Returns the number of files written.
+pub fn write_pseudo(
+    configs: &[LocalizationConfig],
+    project_root: &Path,
+    dist_dir: &Path,
+) -> anyhow::Result<usize> {
+    let mut written = 0;
+
+    for config in configs {
+        let strings = load_strings(&project_root.join(&config.directory).join(&config.default))?;
+        let pseudo: serde_json::Map<String, serde_json::Value> = strings
+            .iter()
+            .map(|(key, text)| (key.clone(), pseudo_localize(text).into()))
+            .collect();
+        let json = serde_json::to_string_pretty(&pseudo)?;
+
+        let dir = dist_dir.join(&config.directory);
+        std::fs::create_dir_all(&dir)?;
+        for language in languages(config, project_root) {
+            std::fs::write(dir.join(&language.file), &json)?;
+            written += 1;
+        }
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudo_keeps_macros() {
+        assert_eq!(
+            pseudo_localize("Press $BTN_JUMP; to continue"),
+            "[!! Préss $BTN_JUMP; tö çöñtíñüé !!]"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Where localization keys are used: Lua sources and BLT menu files
+
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A localization key used by the mod
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocReference {
+    pub key: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Keys used by the BLT menu files (JSON with a `menu_id`) found in `paths`
+pub fn menu_references(paths: &[PathBuf]) -> Vec<LocReference> {
+    let mut references = Vec::new();
+
+    for path in paths {
+        for entry in WalkDir::new(path).sort_by_file_name().into_iter().flatten() {
+            let file = entry.path();
+            if !matches!(
+                file.extension().and_then(|e| e.to_str()),
+                Some("json" | "txt")
+            ) {
+                continue;
+            }
+
+            let Ok(content) = std::fs::read_to_string(file) else {
+                continue;
+            };
+            let Ok(menu) = serde_json::from_str::<serde_json::Value>(&content) else {
+                continue;
+            };
+            if menu.get("menu_id").is_none() {
+                continue;
+            }
+
+            let mut keys = Vec::new();
+            collect_menu_keys(&menu, &mut keys);
+            references.extend(keys.into_iter().map(|key| LocReference {
+                line: line_of(&content, &key),
+                key,
+                file: file.to_path_buf(),
+            }));
+        }
+    }
+
+    references
+}
+
+/// `keys` named in the manifest (e.g. keybind names), located in its text
+pub fn manifest_references(manifest_path: &Path, keys: &[String]) -> Vec<LocReference> {
+    let content = std::fs::read_to_string(manifest_path).unwrap_or_default();
+    keys.iter()
+        .map(|key| LocReference {
+            key: key.clone(),
+            file: manifest_path.to_path_buf(),
+            line: line_of(&content, key),
+        })
+        .collect()
+}
+
+fn collect_menu_keys(node: &serde_json::Value, keys: &mut Vec<String>) {
+    if node.get("localized").and_then(|l| l.as_bool()) == Some(false) {
+        return;
+    }
+
+    for field in ["title", "description", "desc"] {
+        if let Some(key) = node.get(field).and_then(|v| v.as_str())
+            && !key.is_empty()
+        {
+            keys.push(key.to_string());
+        }
+    }
+
+    let Some(items) = node.get("items").and_then(|i| i.as_array()) else {
+        return;
+    };
+    for item in items {
+        match item {
+            // multiple_choice options are loc keys too
+            serde_json::Value::String(key) => keys.push(key.clone()),
+            other => collect_menu_keys(other, keys),
+        }
+    }
+}
+
+fn line_of(content: &str, key: &str) -> usize {
+    let quoted = format!("\"{}\"", key);
+    content
+        .lines()
+        .position(|l| l.contains(&quoted))
+        .map_or(0, |i| i + 1)
+}
+
+/// Add `keys` to a localization file with TODO placeholders, keeping the
+/// existing formatting
+pub fn append_keys(path: &Path, keys: &[String]) -> anyhow::Result<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    let Some(close) = content.rfind('}') else {
+        anyhow::bail!("Malformed localization file {}", path.display());
+    };
+
+    let indent = content
+        .lines()
+        .find(|l| l.trim_start().starts_with('"'))
+        .map(|l| &l[..l.len() - l.trim_start().len()])
+        .unwrap_or("\t");
+
+    let body = content[..close].trim_end();
+    let mut output = body.to_string();
+    for (i, key) in keys.iter().enumerate() {
+        if i > 0 || !body.ends_with('{') {
+            output.push(',');
+        }
+        output.push_str(&format!(
+            "\n{}{} : {}",
+            indent,
+            serde_json::Value::from(key.as_str()),
+            serde_json::Value::from(format!("TODO: {}", key))
+        ));
+    }
+    output.push('\n');
+    output.push_str(&content[close..]);
+
+    std::fs::write(path, output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_keys() {
+        let menu: serde_json::Value = serde_json::from_str(
+            r#"{
+                "menu_id": "m",
+                "title": "m_title",
+                "items": [
+                    {"type": "toggle", "title": "t_title", "description": "t_desc"},
+                    {"type": "multiple_choice", "title": "c_title", "items": ["c_a", "c_b"]},
+                    {"type": "button", "title": "Raw", "localized": false}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut keys = Vec::new();
+        collect_menu_keys(&menu, &mut keys);
+        assert_eq!(
+            keys,
+            vec!["m_title", "t_title", "t_desc", "c_title", "c_a", "c_b"]
+        );
+    }
+}
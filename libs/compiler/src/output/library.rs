@@ -0,0 +1,44 @@
+use super::{Target, remove_stale};
+use crate::Compiler;
+use anyhow::bail;
+use pkg::OdrillProject;
+use std::path::Path;
+
+/// Packages consumed through `use`: sources are checked, no mod files are written
+pub struct LibraryTarget;
+
+impl Target for LibraryTarget {
+    fn name(&self) -> &str {
+        "library"
+    }
+
+    fn bundles_hooks(&self) -> bool {
+        false
+    }
+
+    fn validate(&self, compiler: &Compiler) -> anyhow::Result<()> {
+        let project = compiler.project();
+
+        if !project.manifest.hooks.is_empty() || !project.manifest.keybinds.is_empty() {
+            bail!(
+                "Libraries can't declare [[hooks]] or [[keybinds]]; set [build] target to build a mod"
+            );
+        }
+
+        if !project.manifest.overrides.is_empty() {
+            bail!("Libraries can't declare [overrides]; ship them from the mod using the library");
+        }
+
+        let init = project.root.join("src").join("init.lua");
+        if !init.is_file() {
+            bail!("Library entry point not found: {}", init.display());
+        }
+
+        compiler.verify_file(&init)
+    }
+
+    fn generate(&self, _project: &OdrillProject, dist_dir: &Path) -> anyhow::Result<Vec<String>> {
+        remove_stale(dist_dir, &["mod.txt", "main.xml"])?;
+        Ok(Vec::new())
+    }
+}
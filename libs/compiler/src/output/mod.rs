@@ -0,0 +1,98 @@
+//! Output targets: what `odrill build` writes to dist/ besides the bundles
+//!
+//! The target comes from [`OdrillManifest::target`](pkg::OdrillManifest::target).
+
+mod library;
+
+pub use crate::beardlib::BeardLibTarget;
+pub use crate::superblt::SuperBltTarget;
+pub use library::LibraryTarget;
+
+use crate::Compiler;
+use crate::settings;
+use crate::superblt::keybinds;
+use pkg::OdrillProject;
+use pkg::manifest::{HookConfig, OdrillManifest, TargetKind};
+use std::path::Path;
+
+pub trait Target {
+    fn name(&self) -> &str;
+
+    /// Whether hooks are bundled into dist/
+    fn bundles_hooks(&self) -> bool {
+        true
+    }
+
+    /// Check the project before anything is written
+    fn validate(&self, _compiler: &Compiler) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Write the target files into `dist_dir` once hooks are bundled.
+    /// Returns the names of the generated files.
+    fn generate(&self, project: &OdrillProject, dist_dir: &Path) -> anyhow::Result<Vec<String>>;
+}
+
+/// Target selected by the project manifest
+pub fn for_project(project: &OdrillProject) -> Box<dyn Target> {
+    match project.manifest.target() {
+        TargetKind::SuperBlt => Box::new(SuperBltTarget),
+        TargetKind::BeardLib => Box::new(BeardLibTarget),
+        TargetKind::Library => Box::new(LibraryTarget),
+    }
+}
+
+/// Hooks of the manifest plus the ones odrill generates (settings menu,
+/// default keybind keys)
+pub fn mod_hooks(manifest: &OdrillManifest) -> Vec<HookConfig> {
+    let mut hooks = manifest.hooks.clone();
+    hooks.extend(settings::menu_hook(manifest));
+    hooks.extend(keybinds::defaults_hook(manifest));
+    hooks
+}
+
+/// Write the scripts and data of the generated hooks into dist/.
+/// Returns the written files.
+pub(crate) fn write_generated(
+    project: &OdrillProject,
+    dist_dir: &Path,
+) -> anyhow::Result<Vec<String>> {
+    let mut files = settings::write_dist(project, dist_dir)?;
+    files.extend(keybinds::write_dist(&project.manifest, dist_dir)?);
+    Ok(files)
+}
+
+/// Delete files a previous build with another target left in dist/
+pub(crate) fn remove_stale(dist_dir: &Path, files: &[&str]) -> anyhow::Result<()> {
+    for file in files {
+        let path = dist_dir.join(file);
+        if path.is_file() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fail if any of `files` (relative to `dist_dir`) is missing
+pub(crate) fn check_files<P: AsRef<Path>>(
+    dist_dir: &Path,
+    referenced_by: &str,
+    files: impl IntoIterator<Item = P>,
+) -> anyhow::Result<()> {
+    let missing: Vec<String> = files
+        .into_iter()
+        .filter(|f| !dist_dir.join(f).is_file())
+        .map(|f| f.as_ref().display().to_string())
+        .collect();
+
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "{} references files missing from {}: {}",
+            referenced_by,
+            dist_dir.display(),
+            missing.join(", ")
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! mod_overrides folders from `[overrides]`, shipped in `dist/overrides/<name>`
+
+use anyhow::bail;
+use diesel::HashList;
+use pkg::OdrillProject;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Where overrides are copied, relative to dist/
+pub const DIST_DIR: &str = "overrides";
+
+/// Asset types the game loads from mod_overrides
+const ASSET_EXTENSIONS: &[&str] = &[
+    "animation",
+    "animation_def",
+    "animation_state_machine",
+    "animation_subset",
+    "bnk",
+    "cooked_physics",
+    "effect",
+    "environment",
+    "font",
+    "material_config",
+    "merged_font",
+    "model",
+    "movie",
+    "object",
+    "scene",
+    "sequence_manager",
+    "stream",
+    "texture",
+    "unit",
+];
+
+/// An override copied to dist/
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopiedOverride {
+    pub name: String,
+    pub files: usize,
+}
+
+/// Check every `[overrides]` folder. Files the game can't load are warnings.
+pub fn validate(project: &OdrillProject) -> anyhow::Result<()> {
+    for (name, dir) in &project.manifest.overrides {
+        if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+            bail!("Invalid override name '{}': must be a folder name", name);
+        }
+
+        let src = project.root.join(dir);
+        if !src.is_dir() {
+            bail!("Override '{}' not found: {}", name, src.display());
+        }
+
+        let files = asset_files(&src);
+        if files.is_empty() {
+            bail!("Override '{}' is empty: {}", name, src.display());
+        }
+
+        for file in files {
+            let relative = file.strip_prefix(&src).unwrap_or(&file);
+            match file.extension().and_then(|e| e.to_str()) {
+                Some("dds") => eprintln!(
+                    "Warning: {} in override '{}' must be renamed to .texture",
+                    relative.display(),
+                    name
+                ),
+                Some(ext) if ASSET_EXTENSIONS.contains(&ext) => {}
+                _ => eprintln!(
+                    "Warning: {} in override '{}' is not a game asset type",
+                    relative.display(),
+                    name
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn about override files whose asset path or type is not in `hashlist`:
+/// mod_overrides can only replace existing assets. Returns the number of
+/// unknown files.
+pub fn check_hashlist(project: &OdrillProject, hashlist: &HashList) -> usize {
+    let mut unknown = 0;
+
+    for (name, dir) in &project.manifest.overrides {
+        let src = project.root.join(dir);
+        for file in asset_files(&src) {
+            let Ok(relative) = file.strip_prefix(&src) else {
+                continue;
+            };
+            let asset = relative.with_extension("");
+            let asset = asset.to_string_lossy().replace('\\', "/");
+            let kind = relative.extension().unwrap_or_default().to_string_lossy();
+
+            if !hashlist.contains(&asset) || !hashlist.contains(&kind) {
+                eprintln!(
+                    "Warning: {} in override '{}' does not replace a known game asset",
+                    relative.display(),
+                    name
+                );
+                unknown += 1;
+            }
+        }
+    }
+
+    unknown
+}
+
+/// Validate the overrides and copy them to `dist/overrides/<name>`, replacing
+/// the previous build's
+pub fn copy(project: &OdrillProject, dist_dir: &Path) -> anyhow::Result<Vec<CopiedOverride>> {
+    let dist = dist_dir.join(DIST_DIR);
+    if dist.is_dir() {
+        std::fs::remove_dir_all(&dist)?;
+    }
+
+    validate(project)?;
+
+    let mut copied = Vec::new();
+    for (name, dir) in &project.manifest.overrides {
+        let src = project.root.join(dir);
+        let files = asset_files(&src);
+        for file in &files {
+            let target = dist.join(name).join(file.strip_prefix(&src)?);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(file, target)?;
+        }
+
+        copied.push(CopiedOverride {
+            name: name.clone(),
+            files: files.len(),
+        });
+    }
+
+    Ok(copied)
+}
+
+/// Files of an override folder, without hidden ones (`.gitkeep`, ...)
+fn asset_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect()
+}
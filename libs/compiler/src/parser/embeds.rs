@@ -0,0 +1,37 @@
+//! Embed directive types and parsing
+
+use super::blank_comments;
+use regex::Regex;
+use std::ops::Range;
+
+/// Represents a compile-time data embed: `odrill.embed("data/file.toml")`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbedDirective {
+    pub file_path: String,
+    pub line: usize,
+    pub full_match: String,
+    /// Byte range of the call in the source
+    pub span: Range<usize>,
+}
+
+/// Extract embed directives from source using provided patterns
+pub fn extract_embeds(
+    source: &str,
+    embed_pattern: &Regex,
+    comment_pattern: &Regex,
+) -> Vec<EmbedDirective> {
+    let source_no_comments = blank_comments(source, comment_pattern);
+
+    embed_pattern
+        .captures_iter(&source_no_comments)
+        .map(|cap| {
+            let call = cap.get(0).expect("group 0 always matches");
+            EmbedDirective {
+                file_path: cap[1].to_string(),
+                line: source_no_comments[..call.start()].matches('\n').count() + 1,
+                full_match: call.as_str().to_string(),
+                span: call.range(),
+            }
+        })
+        .collect()
+}
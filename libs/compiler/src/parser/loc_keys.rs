@@ -0,0 +1,47 @@
+//! Localization key references: `managers.localization:text("key")`
+
+use super::blank_comments;
+use regex::Regex;
+
+/// A localization key used in Lua source
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocKeyRef {
+    pub key: String,
+    pub line: usize,
+}
+
+/// Extract localization key references from source using provided patterns
+pub fn extract_loc_keys(
+    source: &str,
+    loc_key_pattern: &Regex,
+    comment_pattern: &Regex,
+) -> Vec<LocKeyRef> {
+    let source_no_comments = blank_comments(source, comment_pattern);
+
+    source_no_comments
+        .lines()
+        .enumerate()
+        .flat_map(|(line_num, line)| {
+            loc_key_pattern
+                .captures_iter(line)
+                .map(move |cap| LocKeyRef {
+                    key: cap[1].to_string(),
+                    line: line_num + 1,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::LuaParser;
+
+    #[test]
+    fn test_loc_key_lines_after_block_comment() {
+        let source = "--[[\nmanagers.localization:text(\"hidden\")\n]]\nlocal t = managers.localization:text(\"shown\") -- text(\"no\")\n";
+        let keys = LuaParser::new("--").extract_loc_keys(source);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, "shown");
+        assert_eq!(keys[0].line, 4);
+    }
+}
@@ -0,0 +1,99 @@
+//! Class methods: `function Class:method(...)` definitions, `Class = Class or class(Base)`
+//! declarations and `Hooks:PostHook(Class, "method", ...)` calls
+
+use super::blank_comments;
+use regex::Regex;
+
+/// A method defined on a class table
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDef {
+    pub class: String,
+    pub method: String,
+    pub params: Vec<String>,
+    /// Defined with `.` instead of `:` (no implicit `self`)
+    pub is_static: bool,
+    pub line: usize,
+}
+
+/// A class declared with `class()`, and its base class
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDef {
+    pub name: String,
+    pub parent: Option<String>,
+}
+
+/// A method hooked through the BLT `Hooks` API
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodHookRef {
+    pub class: String,
+    pub method: String,
+    pub line: usize,
+}
+
+pub fn extract_methods(source: &str, method_pattern: &Regex) -> Vec<MethodDef> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let cap = method_pattern.captures(line)?;
+            Some(MethodDef {
+                class: cap[1].to_string(),
+                is_static: &cap[2] == ".",
+                method: cap[3].to_string(),
+                params: cap[4]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                line: i + 1,
+            })
+        })
+        .collect()
+}
+
+pub fn extract_classes(source: &str, class_pattern: &Regex) -> Vec<ClassDef> {
+    class_pattern
+        .captures_iter(source)
+        .map(|cap| ClassDef {
+            name: cap[1].to_string(),
+            parent: cap.get(2).map(|p| p.as_str().to_string()),
+        })
+        .collect()
+}
+
+pub fn extract_method_hooks(
+    source: &str,
+    hook_pattern: &Regex,
+    comment_pattern: &Regex,
+) -> Vec<MethodHookRef> {
+    let source_no_comments = blank_comments(source, comment_pattern);
+
+    source_no_comments
+        .lines()
+        .enumerate()
+        .flat_map(|(line_num, line)| {
+            hook_pattern
+                .captures_iter(line)
+                .map(move |cap| MethodHookRef {
+                    class: cap[1].to_string(),
+                    method: cap[2].to_string(),
+                    line: line_num + 1,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::LuaParser;
+
+    #[test]
+    fn test_method_hook_lines_after_block_comment() {
+        let source = "--[[\nHooks:PostHook(HUDManager, \"old\", \"id\", f)\n]]\nHooks:PostHook(HUDManager, \"new\", \"id\", f)\n";
+        let hooks = LuaParser::new("--").extract_method_hooks(source);
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0].method, "new");
+        assert_eq!(hooks[0].line, 4);
+    }
+}
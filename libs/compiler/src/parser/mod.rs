@@ -1,16 +1,38 @@
 //! Lua parser for extracting include/require/use statements
 
+mod embeds;
 mod functions;
 mod includes;
+mod loc_keys;
+mod methods;
 mod uses;
 
+pub use embeds::{EmbedDirective, extract_embeds};
 pub use functions::{FunctionDef, build_symbol_table, extract_functions};
 pub use includes::{IncludeType, ModuleInclude, extract_includes};
+pub use loc_keys::{LocKeyRef, extract_loc_keys};
+pub use methods::{ClassDef, MethodDef, MethodHookRef};
 pub use uses::{UseDirective, extract_uses};
 
+use crate::vfs::FileSystem;
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Replace comments with spaces, keeping their newlines, so line numbers and
+/// byte offsets still match the original source
+pub(crate) fn blank_comments<'a>(source: &'a str, comment_pattern: &Regex) -> Cow<'a, str> {
+    comment_pattern.replace_all(source, |caps: &regex::Captures| {
+        caps[0]
+            .chars()
+            .map(|c| match c {
+                '\n' => "\n".to_string(),
+                c => " ".repeat(c.len_utf8()),
+            })
+            .collect::<String>()
+    })
+}
 
 /// Parser for extracting includes/uses from Lua source code
 pub struct LuaParser {
@@ -22,6 +44,12 @@ pub struct LuaParser {
     use_multi_pattern: Regex,
     use_all_pattern: Regex,
     function_pattern: Regex,
+    embed_pattern: Regex,
+    loc_key_pattern: Regex,
+    method_pattern: Regex,
+    class_pattern: Regex,
+    method_hook_pattern: Regex,
+    source_extensions: Vec<String>,
 }
 
 impl LuaParser {
@@ -39,9 +67,52 @@ impl LuaParser {
             use_multi_pattern: Regex::new(r#"^use\s+(.+)::\{([^}]+)\}\s*$"#).unwrap(),
             use_all_pattern: Regex::new(r#"^use\s+(.+)::\*\s*$"#).unwrap(),
             function_pattern: Regex::new(r#"^\s*(local\s+)?function\s+([\w\.:]+)\s*\("#).unwrap(),
+            embed_pattern: Regex::new(r#"odrill\.embed\s*\(\s*["']([^"']+)["']\s*\)"#).unwrap(),
+            loc_key_pattern: Regex::new(
+                r#"managers\.localization:(?:text|to_upper_text)\s*\(\s*["']([^"']+)["']"#,
+            )
+            .unwrap(),
+            method_pattern: Regex::new(
+                r#"^\s*function\s+([A-Za-z_]\w*)([:.])([A-Za-z_]\w*)\s*\(([^)]*)\)"#,
+            )
+            .unwrap(),
+            class_pattern: Regex::new(
+                r#"(?m)^\s*([A-Za-z_]\w*)\s*=\s*[A-Za-z_]\w*\s+or\s+class\s*\(\s*([A-Za-z_]\w*)?\s*\)"#,
+            )
+            .unwrap(),
+            method_hook_pattern: Regex::new(
+                r#"Hooks:(?:PostHook|PreHook|OverrideFunction)\s*\(\s*([A-Za-z_]\w*)\s*,\s*["']([A-Za-z_]\w*)["']"#,
+            )
+            .unwrap(),
+            source_extensions: vec!["lua".to_string()],
+        }
+    }
+
+    /// Accept modules with this extension (handled by a preprocessor) when resolving
+    pub fn add_source_extension(&mut self, extension: &str) {
+        if !self.source_extensions.iter().any(|e| e == extension) {
+            self.source_extensions.push(extension.to_string());
         }
     }
 
+    pub fn is_source_extension(&self, extension: &str) -> bool {
+        self.source_extensions.iter().any(|e| e == extension)
+    }
+
+    /// Find the module file for an extension-less base path:
+    /// `<base>.<ext>` first, then `<base>/mod.<ext>`
+    pub fn find_module_file(&self, base: &Path, fs: &dyn FileSystem) -> Option<PathBuf> {
+        self.source_extensions
+            .iter()
+            .map(|ext| base.with_extension(ext))
+            .chain(
+                self.source_extensions
+                    .iter()
+                    .map(|ext| base.join("mod").with_extension(ext)),
+            )
+            .find(|p| fs.is_file(p))
+    }
+
     pub fn extract_includes(&self, source: &str) -> Vec<ModuleInclude> {
         includes::extract_includes(
             source,
@@ -61,6 +132,26 @@ impl LuaParser {
         )
     }
 
+    pub fn extract_embeds(&self, source: &str) -> Vec<EmbedDirective> {
+        embeds::extract_embeds(source, &self.embed_pattern, &self.comment_pattern)
+    }
+
+    pub fn extract_loc_keys(&self, source: &str) -> Vec<LocKeyRef> {
+        loc_keys::extract_loc_keys(source, &self.loc_key_pattern, &self.comment_pattern)
+    }
+
+    pub fn extract_methods(&self, source: &str) -> Vec<MethodDef> {
+        methods::extract_methods(source, &self.method_pattern)
+    }
+
+    pub fn extract_classes(&self, source: &str) -> Vec<ClassDef> {
+        methods::extract_classes(source, &self.class_pattern)
+    }
+
+    pub fn extract_method_hooks(&self, source: &str) -> Vec<MethodHookRef> {
+        methods::extract_method_hooks(source, &self.method_hook_pattern, &self.comment_pattern)
+    }
+
     pub fn extract_functions(&self, source: &str) -> Vec<FunctionDef> {
         functions::extract_functions(source, &self.function_pattern)
     }
@@ -76,8 +167,9 @@ impl LuaParser {
     pub fn resolve_module_path(
         &self,
         module_path: &str,
-        current_file: &std::path::Path,
-        src_root: &std::path::Path,
+        current_file: &Path,
+        src_root: &Path,
+        fs: &dyn FileSystem,
     ) -> Option<PathBuf> {
         let current_dir = current_file.parent()?;
         let resolved = if module_path.starts_with("./") || module_path.starts_with("../") {
@@ -86,23 +178,36 @@ impl LuaParser {
             src_root.join(module_path)
         };
 
-        try_resolve_path(&resolved, module_path)
-    }
-}
-
-fn try_resolve_path(resolved: &PathBuf, module_path: &str) -> Option<PathBuf> {
-    if resolved.exists() {
-        return Some(resolved.clone());
-    }
-    if !module_path.ends_with(".lua") {
-        let with_ext = resolved.with_extension("lua");
-        if with_ext.exists() {
-            return Some(with_ext);
+        if fs.is_file(&resolved) {
+            return Some(resolved);
         }
-        let as_mod = resolved.join("mod.lua");
-        if as_mod.exists() {
-            return Some(as_mod);
+
+        let has_source_ext = Path::new(module_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| self.source_extensions.iter().any(|s| s == e));
+        if has_source_ext {
+            return None;
         }
+
+        self.find_module_file(&resolved, fs)
+    }
+
+    /// Resolve an `odrill.embed` path: relative to the current file when it
+    /// starts with `./` or `../`, otherwise relative to the project root.
+    pub fn resolve_embed_path(
+        &self,
+        file_path: &str,
+        current_file: &Path,
+        project_root: &Path,
+        fs: &dyn FileSystem,
+    ) -> Option<PathBuf> {
+        let resolved = if file_path.starts_with("./") || file_path.starts_with("../") {
+            current_file.parent()?.join(file_path)
+        } else {
+            project_root.join(file_path)
+        };
+
+        fs.is_file(&resolved).then_some(resolved)
     }
-    None
 }
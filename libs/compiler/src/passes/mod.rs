@@ -0,0 +1,58 @@
+//! Transform passes run by the compiler on every bundle
+//!
+//! Passes are applied in registration order. For each pass, every file and
+//! symbol chunk is transformed first, then the whole bundle. Once all passes
+//! ran, the bundle is rendered and each pass gets the final output.
+
+mod strip_comments;
+mod tag_logs;
+
+pub use strip_comments::StripComments;
+pub use tag_logs::{TagLogs, log_prefix};
+
+use crate::engine::Bundle;
+use pkg::OdrillProject;
+use std::path::Path;
+
+/// Information available to passes
+pub struct PassContext<'a> {
+    pub project: &'a OdrillProject,
+    /// Entry file of the hook being compiled
+    pub entry: &'a Path,
+}
+
+/// A compiler transform. All methods default to no-ops.
+pub trait Pass {
+    fn name(&self) -> &str;
+
+    /// Transform the body of a source file
+    fn transform_file(
+        &self,
+        _ctx: &PassContext,
+        _file: &Path,
+        _code: &mut String,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Transform a symbol imported with `use`
+    fn transform_symbol(
+        &self,
+        _ctx: &PassContext,
+        _name: &str,
+        _file: &Path,
+        _code: &mut String,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Transform the parsed bundle (reorder, add or drop chunks, edit the prelude)
+    fn transform_bundle(&self, _ctx: &PassContext, _bundle: &mut Bundle) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Transform the rendered output
+    fn transform_output(&self, _ctx: &PassContext, _output: &mut String) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
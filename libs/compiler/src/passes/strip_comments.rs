@@ -0,0 +1,131 @@
+//! Built-in pass removing Lua comments (`[options] strip_comments = true`)
+
+use super::{Pass, PassContext};
+use std::path::Path;
+
+/// Removes `--` and `--[[ ]]` comments while leaving string literals intact
+pub struct StripComments;
+
+impl Pass for StripComments {
+    fn name(&self) -> &str {
+        "strip-comments"
+    }
+
+    fn transform_file(
+        &self,
+        _ctx: &PassContext,
+        _file: &Path,
+        code: &mut String,
+    ) -> anyhow::Result<()> {
+        *code = strip_lua_comments(code);
+        Ok(())
+    }
+
+    fn transform_symbol(
+        &self,
+        _ctx: &PassContext,
+        _name: &str,
+        _file: &Path,
+        code: &mut String,
+    ) -> anyhow::Result<()> {
+        *code = strip_lua_comments(code);
+        Ok(())
+    }
+}
+
+/// Strip comments, trailing whitespace and repeated blank lines
+pub fn strip_lua_comments(code: &str) -> String {
+    let bytes = code.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote && bytes[i] != b'\n' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(bytes.len());
+                out.extend_from_slice(&bytes[start..i]);
+            }
+            b'[' if long_bracket_level(bytes, i).is_some() => {
+                let level = long_bracket_level(bytes, i).unwrap_or(0);
+                let end = find_long_bracket_end(bytes, i + level + 2, level);
+                out.extend_from_slice(&bytes[i..end]);
+                i = end;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i += 2;
+                if let Some(level) = long_bracket_level(bytes, i) {
+                    i = find_long_bracket_end(bytes, i + level + 2, level);
+                } else {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                }
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    let stripped = String::from_utf8_lossy(&out);
+    let mut result = String::with_capacity(stripped.len());
+    let mut previous_blank = true;
+
+    for line in stripped.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            if previous_blank {
+                continue;
+            }
+            previous_blank = true;
+        } else {
+            previous_blank = false;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    result.trim_end().to_string()
+}
+
+/// Level of a long bracket (`[[` = 0, `[==[` = 2) opening at `i`
+fn long_bracket_level(bytes: &[u8], i: usize) -> Option<usize> {
+    if bytes.get(i) != Some(&b'[') {
+        return None;
+    }
+    let level = bytes[i + 1..].iter().take_while(|&&b| b == b'=').count();
+    (bytes.get(i + 1 + level) == Some(&b'[')).then_some(level)
+}
+
+/// Index just past the `]=*]` closing a long bracket of `level`
+fn find_long_bracket_end(bytes: &[u8], from: usize, level: usize) -> usize {
+    let mut close = vec![b']'];
+    close.extend(std::iter::repeat_n(b'=', level));
+    close.push(b']');
+
+    bytes
+        .get(from..)
+        .and_then(|rest| rest.windows(close.len()).position(|w| w == close))
+        .map(|pos| from + pos + close.len())
+        .unwrap_or(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments_keeps_strings() {
+        let code = "local a = \"--not a comment\" -- comment\n--[[ block\ncomment ]]\nlocal b = [[--raw]]\n--[==[ x ]==]print(a)";
+        assert_eq!(
+            strip_lua_comments(code),
+            "local a = \"--not a comment\"\n\nlocal b = [[--raw]]\nprint(a)"
+        );
+    }
+}
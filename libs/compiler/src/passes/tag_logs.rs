@@ -0,0 +1,85 @@
+//! Pass prefixing a bundle's `log()` output with its package name, so
+//! `odrill run` can tell which project a log line comes from
+
+use super::{Pass, PassContext};
+use crate::engine::Bundle;
+use crate::engine::embed::{LuaValue, to_lua_literal};
+
+/// Shadows `log` in every bundle with a version prepending [`log_prefix`]
+pub struct TagLogs {
+    prefix: String,
+}
+
+impl TagLogs {
+    pub fn new(package_name: &str) -> Self {
+        Self {
+            prefix: log_prefix(package_name),
+        }
+    }
+}
+
+/// Text prepended to the `log()` output of a package built with [`TagLogs`]
+pub fn log_prefix(package_name: &str) -> String {
+    format!("[{}] ", package_name)
+}
+
+impl Pass for TagLogs {
+    fn name(&self) -> &str {
+        "tag-logs"
+    }
+
+    fn transform_bundle(&self, _ctx: &PassContext, bundle: &mut Bundle) -> anyhow::Result<()> {
+        bundle.prelude.push_str(&format!(
+            "local log = function(message) _G.log({} .. tostring(message)) end\n",
+            to_lua_literal(&LuaValue::String(self.prefix.clone()))
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Compiler;
+    use crate::vfs::MemoryFs;
+    use pkg::OdrillProject;
+    use std::path::Path;
+
+    #[test]
+    fn test_tag_logs() {
+        let manifest = toml::from_str(
+            r#"
+            [package]
+            name = "my-mod"
+            version = "0.1.0"
+            authors = []
+
+            [[hooks]]
+            id = "lib/managers/menumanager"
+            entry = "src/main.lua"
+            output = "main.lua"
+            "#,
+        )
+        .unwrap();
+        let root = Path::new("/virtual/my-mod");
+        let source = MemoryFs::new();
+        source.insert(root.join("src/main.lua"), "log(\"hello\")\n");
+        let output = MemoryFs::new();
+
+        Compiler::new(OdrillProject {
+            root: root.to_path_buf(),
+            manifest,
+        })
+        .with_source(source)
+        .with_output(output.clone())
+        .with_pass(TagLogs::new("my-mod"))
+        .compile_all()
+        .unwrap();
+
+        let bundle = String::from_utf8(output.get(root.join("dist/main.lua")).unwrap()).unwrap();
+        let shadow = bundle
+            .find("local log = function(message) _G.log(\"[my-mod] \" .. tostring(message)) end")
+            .unwrap();
+        assert!(shadow < bundle.find("log(\"hello\")").unwrap());
+    }
+}
@@ -0,0 +1,114 @@
+//! Lua templates for the generated settings code
+
+/// `odrill::settings`. Values live in a global so every hook shares them.
+const MODULE: &str = r#"-- Generated by odrill from [[settings]]
+
+local function ODRILL_SETTINGS()
+    local settings = rawget(_G, "__PREFIX___settings")
+    if settings then
+        return settings
+    end
+
+    settings = {
+        path = SavePath .. "__PREFIX___settings.json",
+        values = __DEFAULTS__,
+    }
+
+    local file = io.open(settings.path, "r")
+    if file then
+        local ok, saved = pcall(json.decode, file:read("*all"))
+        file:close()
+        if ok and type(saved) == "table" then
+            for key, value in pairs(saved) do
+                if settings.values[key] ~= nil then
+                    settings.values[key] = value
+                end
+            end
+        end
+    end
+
+    rawset(_G, "__PREFIX___settings", settings)
+    return settings
+end
+
+local function get(key)
+    return ODRILL_SETTINGS().values[key]
+end
+
+local function set(key, value)
+    ODRILL_SETTINGS().values[key] = value
+end
+
+local function save()
+    local settings = ODRILL_SETTINGS()
+    local file = io.open(settings.path, "w+")
+    if file then
+        file:write(json.encode(settings.values))
+        file:close()
+    end
+end
+"#;
+
+/// Menu hook: default strings, callbacks and the menu itself
+const MENU_HOOK: &str = r#"
+-- ModPath is only valid while this file loads, not in the callbacks below
+local mod_path = ModPath
+local ODRILL_SETTINGS_STRINGS = __STRINGS__
+
+Hooks:Add("LocalizationManagerPostInit", "__PREFIX___settings_loc", function(loc)
+    for key, text in pairs(ODRILL_SETTINGS_STRINGS) do
+        if not loc:exists(key) then
+            loc:add_localized_strings({ [key] = text })
+        end
+    end
+end)
+
+Hooks:Add("MenuManagerInitialize", "__PREFIX___settings_menu", function()
+    MenuCallbackHandler.__PREFIX___settings_changed = function(self, item)
+        local value = item:value()
+        if value == "on" then
+            value = true
+        elseif value == "off" then
+            value = false
+        end
+        set(item:name(), value)
+    end
+
+    MenuCallbackHandler.__PREFIX___settings_save = function(self, item)
+        save()
+    end
+__KEYBINDS__
+    MenuHelper:LoadFromJsonFile(mod_path .. "odrill/settings_menu.json", nil, ODRILL_SETTINGS().values)
+end)
+"#;
+
+const KEYBIND: &str = r#"
+    MenuCallbackHandler.__PREFIX___keybind___ID__ = function(self)
+        Hooks:Call("__PREFIX___keybind___ID__")
+    end
+"#;
+
+pub(super) fn module(prefix: &str, defaults: &str) -> String {
+    MODULE
+        .replace("__PREFIX__", prefix)
+        .replace("__DEFAULTS__", &indent(defaults))
+}
+
+pub(super) fn menu_hook(prefix: &str, module: &str, strings: &str, keybinds: &[String]) -> String {
+    let keybinds: String = keybinds
+        .iter()
+        .map(|id| KEYBIND.replace("__ID__", id))
+        .collect();
+
+    let hook = MENU_HOOK
+        .replace("__STRINGS__", strings)
+        .replace("__KEYBINDS__", &keybinds)
+        .replace("__PREFIX__", prefix);
+
+    format!("{}{}", module, hook)
+}
+
+/// Indent continuation lines of a table literal nested two levels deep
+fn indent(literal: &str) -> String {
+    literal.replace('\n', "\n        ")
+}
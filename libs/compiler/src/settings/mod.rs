@@ -0,0 +1,430 @@
+//! Mod settings from `[[settings]]`: the `odrill::settings` module, the BLT
+//! options menu and its default localization
+
+mod lua;
+
+use crate::engine::embed::{LuaValue, to_lua_literal};
+use crate::error::BundlerError;
+use pkg::OdrillProject;
+use pkg::manifest::{HookConfig, HookKind, OdrillManifest, SettingConfig, SettingKind};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Game script the generated menu hook is attached to
+pub const MENU_HOOK_ID: &str = "lib/managers/menumanager";
+/// Menu hook script, relative to dist/
+pub const MENU_HOOK_OUTPUT: &str = "odrill/settings.lua";
+/// Menu definition, relative to dist/
+pub const MENU_JSON_OUTPUT: &str = "odrill/settings_menu.json";
+
+/// Where `use odrill::settings::...` is read from
+pub fn module_path(project_root: &Path) -> PathBuf {
+    project_root
+        .join("target")
+        .join("odrill")
+        .join("settings.lua")
+}
+
+/// Generated hook registering the settings menu, if the project has settings
+pub fn menu_hook(manifest: &OdrillManifest) -> Option<HookConfig> {
+    (!manifest.settings.is_empty()).then(|| HookConfig {
+        kind: HookKind::Post,
+        id: MENU_HOOK_ID.to_string(),
+        entry: PathBuf::from(MENU_HOOK_OUTPUT),
+        output: PathBuf::from(MENU_HOOK_OUTPUT),
+        priority: 0,
+        global: None,
+    })
+}
+
+/// Identifier prefix of a package's settings: non-alphanumerics become `_`,
+/// and a leading digit gets a `_` so it stays a valid Lua identifier
+pub fn prefix(package_name: &str) -> String {
+    let prefix: String = package_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if prefix.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", prefix)
+    } else {
+        prefix
+    }
+}
+
+/// BLT keybind ids of keybind settings with a default key, and that key
+pub fn keybind_defaults(manifest: &OdrillManifest) -> Vec<(String, String)> {
+    let prefix = prefix(&manifest.package.name);
+    manifest
+        .settings
+        .iter()
+        .filter(|s| s.kind == SettingKind::Keybind)
+        .filter_map(|s| {
+            let key = s.default.as_ref()?.as_str()?;
+            Some((format!("{}_{}", prefix, s.id), key.to_string()))
+        })
+        .collect()
+}
+
+/// Generated code and data for a project's settings
+pub struct Settings<'a> {
+    /// Identifier prefix for globals, callbacks and loc keys
+    prefix: String,
+    manifest: &'a OdrillManifest,
+}
+
+impl<'a> Settings<'a> {
+    /// Validate `[[settings]]`. Returns `None` when the project has none.
+    pub fn new(manifest: &'a OdrillManifest) -> Result<Option<Self>, BundlerError> {
+        if manifest.settings.is_empty() {
+            return Ok(None);
+        }
+
+        for setting in &manifest.settings {
+            validate(setting)?;
+        }
+
+        Ok(Some(Self {
+            prefix: prefix(&manifest.package.name),
+            manifest,
+        }))
+    }
+
+    fn loc_key(&self, setting: &SettingConfig) -> String {
+        setting
+            .loc_key
+            .clone()
+            .unwrap_or_else(|| format!("{}_{}", self.prefix, setting.id))
+    }
+
+    /// Default value of every setting, keyed by id
+    fn defaults(&self) -> LuaValue {
+        LuaValue::Table(
+            self.manifest
+                .settings
+                .iter()
+                .map(|s| (s.id.clone(), default_value(s)))
+                .collect(),
+        )
+    }
+
+    /// Source of the `odrill::settings` module
+    pub fn module_lua(&self) -> String {
+        lua::module(&self.prefix, &to_lua_literal(&self.defaults()))
+    }
+
+    /// Hook script registering the menu and its default strings
+    pub fn menu_hook_lua(&self) -> String {
+        let keybinds: Vec<String> = self
+            .manifest
+            .settings
+            .iter()
+            .filter(|s| s.kind == SettingKind::Keybind)
+            .map(|s| s.id.clone())
+            .collect();
+
+        lua::menu_hook(
+            &self.prefix,
+            &self.module_lua(),
+            &to_lua_literal(&LuaValue::Table(
+                self.loc_strings()
+                    .into_iter()
+                    .map(|(k, v)| (k, LuaValue::String(v)))
+                    .collect(),
+            )),
+            &keybinds,
+        )
+    }
+
+    /// English text for every generated loc key
+    pub fn loc_strings(&self) -> BTreeMap<String, String> {
+        let pkg = &self.manifest.package;
+        let mut strings = BTreeMap::new();
+
+        strings.insert(format!("{}_settings_title", self.prefix), pkg.name.clone());
+        strings.insert(
+            format!("{}_settings_desc", self.prefix),
+            pkg.description.clone().unwrap_or_default(),
+        );
+
+        for setting in &self.manifest.settings {
+            let key = self.loc_key(setting);
+            strings.insert(format!("{}_title", key), setting.title.clone());
+            strings.insert(
+                format!("{}_desc", key),
+                setting.description.clone().unwrap_or_default(),
+            );
+            for (i, choice) in setting.choices.iter().enumerate() {
+                strings.insert(format!("{}_{}", key, i + 1), choice.clone());
+            }
+        }
+
+        strings
+    }
+
+    /// BLT menu definition (for `MenuHelper:LoadFromJsonFile`)
+    pub fn menu_json(&self) -> serde_json::Value {
+        let items: Vec<serde_json::Value> = self
+            .manifest
+            .settings
+            .iter()
+            .map(|s| {
+                let key = self.loc_key(s);
+                let mut item = json!({
+                    "id": s.id,
+                    "title": format!("{}_title", key),
+                    "description": format!("{}_desc", key),
+                });
+
+                let fields = match s.kind {
+                    SettingKind::Toggle => json!({
+                        "type": "toggle",
+                        "callback": format!("{}_settings_changed", self.prefix),
+                        "value": s.id,
+                        "default_value": default_json(s),
+                    }),
+                    SettingKind::Slider => json!({
+                        "type": "slider",
+                        "callback": format!("{}_settings_changed", self.prefix),
+                        "value": s.id,
+                        "default_value": default_json(s),
+                        "min": s.min,
+                        "max": s.max,
+                        "step": s.step.unwrap_or(1.0),
+                    }),
+                    SettingKind::Choice => json!({
+                        "type": "multiple_choice",
+                        "callback": format!("{}_settings_changed", self.prefix),
+                        "value": s.id,
+                        "default_value": default_json(s),
+                        "items": (1..=s.choices.len())
+                            .map(|i| format!("{}_{}", key, i))
+                            .collect::<Vec<_>>(),
+                    }),
+                    SettingKind::Keybind => json!({
+                        "type": "keybind",
+                        "keybind_id": format!("{}_{}", self.prefix, s.id),
+                        "func": format!("{}_keybind_{}", self.prefix, s.id),
+                        "run_in_menu": true,
+                        "run_in_game": true,
+                    }),
+                };
+
+                if let (Some(item), Some(fields)) = (item.as_object_mut(), fields.as_object()) {
+                    item.extend(fields.clone());
+                }
+                item
+            })
+            .collect();
+
+        json!({
+            "menu_id": format!("{}_settings", self.prefix),
+            "parent_menu_id": "blt_options",
+            "title": format!("{}_settings_title", self.prefix),
+            "description": format!("{}_settings_desc", self.prefix),
+            "back_callback": format!("{}_settings_save", self.prefix),
+            "items": items,
+        })
+    }
+
+    /// Write the menu hook and menu definition into dist/
+    pub fn write_dist(&self, dist_dir: &Path) -> anyhow::Result<()> {
+        let hook = dist_dir.join(MENU_HOOK_OUTPUT);
+        if let Some(parent) = hook.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&hook, self.menu_hook_lua())?;
+        std::fs::write(
+            dist_dir.join(MENU_JSON_OUTPUT),
+            serde_json::to_string_pretty(&self.menu_json())?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Write the settings menu files for a project, if it declares settings.
+/// Returns the generated files.
+pub fn write_dist(project: &OdrillProject, dist_dir: &Path) -> anyhow::Result<Vec<String>> {
+    let Some(settings) = Settings::new(&project.manifest)? else {
+        return Ok(Vec::new());
+    };
+    settings.write_dist(dist_dir)?;
+    Ok(vec![
+        MENU_HOOK_OUTPUT.to_string(),
+        MENU_JSON_OUTPUT.to_string(),
+    ])
+}
+
+fn validate(setting: &SettingConfig) -> Result<(), BundlerError> {
+    let invalid = |message: &str| BundlerError::InvalidConfig {
+        message: format!("setting '{}': {}", setting.id, message),
+    };
+
+    let default = setting.default.as_ref();
+    match setting.kind {
+        SettingKind::Toggle => {
+            if default.is_some_and(|d| !d.is_bool()) {
+                return Err(invalid("default must be true or false"));
+            }
+        }
+        SettingKind::Slider => {
+            let (Some(min), Some(max)) = (setting.min, setting.max) else {
+                return Err(invalid("sliders need `min` and `max`"));
+            };
+            if min >= max {
+                return Err(invalid("`min` must be lower than `max`"));
+            }
+            match default.map(toml_number) {
+                Some(None) => return Err(invalid("default must be a number")),
+                Some(Some(d)) if d < min || d > max => {
+                    return Err(invalid("default is outside `min`..`max`"));
+                }
+                _ => {}
+            }
+        }
+        SettingKind::Choice => {
+            if setting.choices.is_empty() {
+                return Err(invalid("choice settings need `choices`"));
+            }
+            match default.map(|d| d.as_integer()) {
+                Some(None) => return Err(invalid("default must be a choice index")),
+                Some(Some(i)) if i < 1 || i as usize > setting.choices.len() => {
+                    return Err(invalid("default is not a valid choice index (1-based)"));
+                }
+                _ => {}
+            }
+        }
+        SettingKind::Keybind => {
+            if default.is_some_and(|d| !d.is_str()) {
+                return Err(invalid("default must be a key name"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn toml_number(value: &toml::Value) -> Option<f64> {
+    value
+        .as_float()
+        .or_else(|| value.as_integer().map(|i| i as f64))
+}
+
+fn default_value(setting: &SettingConfig) -> LuaValue {
+    match (setting.kind, &setting.default) {
+        (_, Some(toml::Value::Boolean(b))) => LuaValue::Bool(*b),
+        (_, Some(toml::Value::Integer(i))) => LuaValue::Integer(*i),
+        (_, Some(toml::Value::Float(f))) => LuaValue::Float(*f),
+        (_, Some(toml::Value::String(s))) => LuaValue::String(s.clone()),
+        (SettingKind::Toggle, _) => LuaValue::Bool(false),
+        (SettingKind::Slider, _) => LuaValue::Float(setting.min.unwrap_or_default()),
+        (SettingKind::Choice, _) => LuaValue::Integer(1),
+        (SettingKind::Keybind, _) => LuaValue::String(String::new()),
+    }
+}
+
+fn default_json(setting: &SettingConfig) -> serde_json::Value {
+    match default_value(setting) {
+        LuaValue::Bool(b) => b.into(),
+        LuaValue::Integer(i) => i.into(),
+        LuaValue::Float(f) => f.into(),
+        LuaValue::String(s) => s.into(),
+        _ => serde_json::Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_generation() {
+        let manifest: OdrillManifest = toml::from_str(
+            r#"
+            [package]
+            name = "my-mod"
+            version = "1.0.0"
+            authors = []
+
+            [[settings]]
+            id = "enabled"
+            type = "toggle"
+            title = "Enabled"
+            default = true
+
+            [[settings]]
+            id = "style"
+            type = "choice"
+            title = "Style"
+            choices = ["Classic", "Modern"]
+            default = 2
+            "#,
+        )
+        .unwrap();
+
+        let settings = Settings::new(&manifest).unwrap().unwrap();
+        let menu = settings.menu_json();
+        assert_eq!(menu["menu_id"], "my_mod_settings");
+        assert_eq!(menu["items"][0]["default_value"], true);
+        assert_eq!(
+            menu["items"][1]["items"],
+            json!(["my_mod_style_1", "my_mod_style_2"])
+        );
+        assert_eq!(settings.loc_strings()["my_mod_style_2"], "Modern");
+        assert!(settings.module_lua().contains("style = 2,"));
+        assert!(
+            settings
+                .menu_hook_lua()
+                .contains(r#"LoadFromJsonFile(mod_path .. "odrill/settings_menu.json""#)
+        );
+    }
+
+    #[test]
+    fn test_slider_default_out_of_range() {
+        let manifest: OdrillManifest = toml::from_str(
+            r#"
+            [package]
+            name = "m"
+            version = "1.0.0"
+            authors = []
+
+            [[settings]]
+            id = "size"
+            type = "slider"
+            title = "Size"
+            min = 1
+            max = 10
+            default = 20
+            "#,
+        )
+        .unwrap();
+
+        assert!(Settings::new(&manifest).is_err());
+    }
+
+    #[test]
+    fn test_keybind_default() {
+        let manifest: OdrillManifest = toml::from_str(
+            r#"
+            [package]
+            name = "m"
+            version = "1.0.0"
+            authors = []
+
+            [[settings]]
+            id = "toggle"
+            type = "keybind"
+            title = "Toggle"
+            default = "f1"
+            "#,
+        )
+        .unwrap();
+
+        assert!(Settings::new(&manifest).is_ok());
+        assert_eq!(
+            keybind_defaults(&manifest),
+            vec![("m_toggle".to_string(), "f1".to_string())]
+        );
+        assert_eq!(prefix("2fast-hud"), "_2fast_hud");
+    }
+}
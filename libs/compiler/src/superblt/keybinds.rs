@@ -0,0 +1,116 @@
+//! Default keys of `[[keybinds]]`, applied by a generated hook since mod.txt
+//! can't declare them
+
+use crate::engine::embed::{LuaValue, to_lua_literal};
+use crate::settings;
+use pkg::manifest::{HookConfig, HookKind, OdrillManifest};
+use std::path::{Path, PathBuf};
+
+/// Game script the defaults hook is attached to (keybinds are loaded by then)
+pub const DEFAULTS_HOOK_ID: &str = "lib/managers/menumanager";
+/// Defaults hook script, relative to dist/
+pub const DEFAULTS_HOOK_OUTPUT: &str = "odrill/keybinds.lua";
+
+/// Generated hook binding default keys, if any keybind declares a `key` or
+/// keybind setting a `default`
+pub fn defaults_hook(manifest: &OdrillManifest) -> Option<HookConfig> {
+    defaults(manifest).map(|_| HookConfig {
+        kind: HookKind::Post,
+        id: DEFAULTS_HOOK_ID.to_string(),
+        entry: PathBuf::from(DEFAULTS_HOOK_OUTPUT),
+        output: PathBuf::from(DEFAULTS_HOOK_OUTPUT),
+        priority: 0,
+        global: None,
+    })
+}
+
+/// Write the defaults hook into dist/. Returns the written files.
+pub fn write_dist(manifest: &OdrillManifest, dist_dir: &Path) -> anyhow::Result<Vec<String>> {
+    let Some(defaults) = defaults(manifest) else {
+        return Ok(Vec::new());
+    };
+
+    let path = dist_dir.join(DEFAULTS_HOOK_OUTPUT);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, defaults_lua(&defaults))?;
+
+    Ok(vec![DEFAULTS_HOOK_OUTPUT.to_string()])
+}
+
+/// Keybind id → default key, from `[[keybinds]]` and keybind `[[settings]]`
+fn defaults(manifest: &OdrillManifest) -> Option<LuaValue> {
+    let keys: Vec<(String, LuaValue)> = manifest
+        .keybinds
+        .iter()
+        .filter_map(|k| Some((k.id.clone(), LuaValue::String(k.key.clone()?))))
+        .chain(
+            settings::keybind_defaults(manifest)
+                .into_iter()
+                .map(|(id, key)| (id, LuaValue::String(key))),
+        )
+        .collect();
+
+    (!keys.is_empty()).then_some(LuaValue::Table(keys))
+}
+
+fn defaults_lua(defaults: &LuaValue) -> String {
+    format!(
+        r#"-- Generated by odrill from [[keybinds]] and keybind [[settings]]
+
+for id, key in pairs({}) do
+    local keybind = BLT.Keybinds:get_keybind(id)
+    if keybind and not keybind:HasKey() then
+        keybind:SetKey(key)
+    end
+end
+"#,
+        to_lua_literal(defaults)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::superblt::ModTxt;
+
+    #[test]
+    fn test_keybinds() {
+        let manifest: OdrillManifest = toml::from_str(
+            r#"
+            [package]
+            name = "m"
+            version = "1.0.0"
+            authors = []
+
+            [[keybinds]]
+            id = "m_toggle"
+            name = "m_toggle_name"
+            description = "m_toggle_desc"
+            key = "f5"
+            entry = "src/keybinds/toggle.lua"
+            output = "keybinds/toggle.lua"
+
+            [[keybinds]]
+            id = "m_reset"
+            name = "Reset"
+            localized = false
+            run_in_menu = true
+            entry = "src/keybinds/reset.lua"
+            output = "keybinds/reset.lua"
+            "#,
+        )
+        .unwrap();
+
+        let mod_txt = ModTxt::from_manifest(&manifest);
+        assert_eq!(mod_txt.keybinds.len(), 2);
+        assert_eq!(mod_txt.keybinds[0].script_path, "keybinds/toggle.lua");
+        assert!(mod_txt.keybinds[1].run_in_menu && !mod_txt.keybinds[1].localized);
+        assert_eq!(mod_txt.hooks[0].script_path, DEFAULTS_HOOK_OUTPUT);
+
+        let lua = defaults_lua(&defaults(&manifest).unwrap());
+        assert!(lua.contains("m_toggle = \"f5\""));
+        assert!(!lua.contains("m_reset"));
+    }
+}
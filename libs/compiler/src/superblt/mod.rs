@@ -0,0 +1,127 @@
+//! SuperBLT output target: mod.txt and assets
+
+pub mod keybinds;
+mod mod_txt;
+
+pub use mod_txt::{Keybind, ModHook, ModTxt, ModUpdate, PersistScript, UpdateHost};
+
+use crate::assets;
+use crate::beardlib::MainXml;
+use crate::localization;
+use crate::output::{Target, check_files, remove_stale, write_generated};
+use pkg::OdrillProject;
+use pkg::manifest::OdrillManifest;
+use std::path::Path;
+
+/// Mod loaded by SuperBLT through mod.txt
+pub struct SuperBltTarget;
+
+impl Target for SuperBltTarget {
+    fn name(&self) -> &str {
+        "superblt"
+    }
+
+    fn generate(&self, project: &OdrillProject, dist_dir: &Path) -> anyhow::Result<Vec<String>> {
+        let manifest = &project.manifest;
+        let asset_files = copy_assets(manifest, &project.root, dist_dir)?;
+        localization::check(&manifest.localization, &project.root)?;
+        let generated_files = write_generated(project, dist_dir)?;
+
+        write_mod_txt(&ModTxt::from_manifest(manifest), dist_dir)?;
+        let mut generated = vec!["mod.txt".to_string()];
+        generated.extend(generated_files);
+        generated.extend(asset_files);
+
+        // Older projects register [[localization]] through main.xml
+        if manifest.localization.is_empty() {
+            remove_stale(dist_dir, &["main.xml"])?;
+        } else {
+            let mut main_xml = MainXml::from_project(project);
+            main_xml.hooks.clear();
+            main_xml.write(dist_dir)?;
+            generated.push("main.xml".to_string());
+        }
+
+        Ok(generated)
+    }
+}
+
+/// Write mod.txt after checking the scripts it points to exist
+pub(crate) fn write_mod_txt(mod_txt: &ModTxt, dist_dir: &Path) -> anyhow::Result<()> {
+    check_files(dist_dir, "mod.txt", mod_txt.referenced_files())?;
+    std::fs::write(dist_dir.join("mod.txt"), mod_txt.to_json()?)?;
+    Ok(())
+}
+
+/// Copy assets, the mod image and localization files into dist/
+pub(crate) fn copy_assets(
+    manifest: &OdrillManifest,
+    project_dir: &Path,
+    dist_dir: &Path,
+) -> anyhow::Result<Vec<String>> {
+    // Copy the mod icon referenced by mod.txt
+    if let Some(image) = &manifest.superblt.image {
+        let src = project_dir.join(image);
+        if src.is_file() {
+            let dist = dist_dir.join(image);
+            if let Some(parent) = dist.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&src, &dist)?;
+        } else {
+            eprintln!("Warning: Mod image not found: {}", src.display());
+        }
+    }
+
+    // Assets are copied incrementally and listed in the asset manifest
+    let report = assets::copy(manifest, project_dir, dist_dir)?;
+    let mut generated = Vec::new();
+    if report.copied + report.unchanged > 0 {
+        generated.push(assets::MANIFEST_OUTPUT.to_string());
+    }
+
+    // Copy every [[localization]] directory, and loc/ if it exists
+    // (Backwards compatibility / auto-detection)
+    let mut loc_dirs: Vec<&str> = manifest
+        .localization
+        .iter()
+        .map(|l| l.directory.as_str())
+        .collect();
+    if !loc_dirs.contains(&"loc") {
+        loc_dirs.push("loc");
+    }
+    for dir in loc_dirs {
+        let loc_src = project_dir.join(dir);
+        if loc_src.is_dir() {
+            let loc_dist = dist_dir.join(dir);
+            // Check if already created by assets
+            if !loc_dist.exists() {
+                copy_dir_recursive(&loc_src, &loc_dist)?;
+            }
+        }
+    }
+
+    // Copy localization.lua if it exists
+    let loc_lua_src = project_dir.join("src/localization.lua");
+    if loc_lua_src.exists() {
+        std::fs::copy(&loc_lua_src, dist_dir.join("localization.lua"))?;
+    }
+
+    Ok(generated)
+}
+
+fn copy_dir_recursive(src: &Path, dist: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dist)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let filename = path.file_name().unwrap();
+        let target = dist.join(filename);
+        if path.is_dir() {
+            copy_dir_recursive(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,287 @@
+//! Typed model of SuperBLT's mod.txt
+
+use pkg::manifest::{HookConfig, HookKind, OdrillManifest};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Contents of a SuperBLT mod.txt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModTxt {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blt_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updates: Vec<ModUpdate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_hooks: Vec<ModHook>,
+    #[serde(default)]
+    pub hooks: Vec<ModHook>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub persist_scripts: Vec<PersistScript>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keybinds: Vec<Keybind>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModHook {
+    pub hook_id: String,
+    pub script_path: String,
+}
+
+/// Script run every frame while `global` is nil
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistScript {
+    pub global: String,
+    pub script_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keybind {
+    pub keybind_id: String,
+    pub name: String,
+    pub description: String,
+    pub script_path: String,
+    pub run_in_menu: bool,
+    pub run_in_game: bool,
+    pub localized: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModUpdate {
+    pub identifier: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<UpdateHost>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateHost {
+    pub meta: String,
+}
+
+impl ModTxt {
+    /// Build mod.txt from the manifest. Hooks sharing a game script run in
+    /// order of descending `priority`.
+    pub fn from_manifest(manifest: &OdrillManifest) -> Self {
+        let pkg = &manifest.package;
+        let blt = &manifest.superblt;
+
+        let all_hooks = crate::output::mod_hooks(manifest);
+        let mut hooks: Vec<&HookConfig> = all_hooks.iter().collect();
+        hooks.sort_by_key(|h| std::cmp::Reverse(h.priority));
+
+        let game_hooks = |kind: HookKind| {
+            hooks
+                .iter()
+                .filter(|h| {
+                    h.kind == kind || (kind == HookKind::Post && h.kind == HookKind::Wildcard)
+                })
+                .map(|h| ModHook {
+                    hook_id: match h.kind {
+                        HookKind::Wildcard => "*".to_string(),
+                        _ => h.id.clone(),
+                    },
+                    script_path: script_path(&h.output),
+                })
+                .collect()
+        };
+
+        Self {
+            name: pkg.name.clone(),
+            description: pkg.description.clone().unwrap_or_default(),
+            author: pkg.authors.join(", "),
+            contact: blt.contact.clone(),
+            version: pkg.version.clone(),
+            blt_version: blt.blt_version,
+            color: blt.color.clone(),
+            image: blt.image.as_deref().map(script_path),
+            priority: blt.priority,
+            updates: blt
+                .updates
+                .iter()
+                .map(|u| ModUpdate {
+                    identifier: u.identifier.clone(),
+                    host: u.host.clone().map(|meta| UpdateHost { meta }),
+                    display_name: u.display_name.clone(),
+                    install_dir: u.install_dir.clone(),
+                })
+                .collect(),
+            pre_hooks: game_hooks(HookKind::Pre),
+            hooks: game_hooks(HookKind::Post),
+            persist_scripts: hooks
+                .iter()
+                .filter(|h| h.kind == HookKind::Persist)
+                .map(|h| PersistScript {
+                    global: h.global.clone().unwrap_or_default(),
+                    script_path: script_path(&h.output),
+                })
+                .collect(),
+            keybinds: manifest
+                .keybinds
+                .iter()
+                .map(|k| Keybind {
+                    keybind_id: k.id.clone(),
+                    name: k.name.clone(),
+                    description: k.description.clone(),
+                    script_path: script_path(&k.output),
+                    run_in_menu: k.run_in_menu,
+                    run_in_game: k.run_in_game,
+                    localized: k.localized,
+                })
+                .collect(),
+        }
+    }
+
+    /// Files mod.txt points to, relative to the mod folder
+    pub fn referenced_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = self
+            .pre_hooks
+            .iter()
+            .chain(&self.hooks)
+            .map(|h| h.script_path.clone())
+            .collect();
+        files.extend(self.persist_scripts.iter().map(|p| p.script_path.clone()));
+        files.extend(self.keybinds.iter().map(|k| k.script_path.clone()));
+        files.extend(self.image.clone());
+        files
+    }
+
+    /// Serialize as pretty JSON with 4-space indentation
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        let mut out = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+        self.serialize(&mut serializer)?;
+        Ok(String::from_utf8(out)?)
+    }
+}
+
+/// Paths in mod.txt are relative to the mod folder and always use `/`
+fn script_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(toml: &str) -> OdrillManifest {
+        toml::from_str(toml).unwrap()
+    }
+
+    /// Compare against `snapshots/<name>`; set `ODRILL_UPDATE_SNAPSHOTS=1` to rewrite it
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/superblt/snapshots")
+            .join(name);
+
+        if std::env::var_os("ODRILL_UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(actual, expected, "snapshot {} differs", name);
+    }
+
+    #[test]
+    fn test_mod_txt_minimal() {
+        let manifest = manifest(
+            r#"
+            [package]
+            name = "Minimal"
+            version = "0.1.0"
+            authors = ["A"]
+
+            [[hooks]]
+            id = "lib/managers/menumanager"
+            entry = "src/main.lua"
+            output = "hooks/main.lua"
+            "#,
+        );
+
+        assert_snapshot(
+            "minimal.mod.txt",
+            &ModTxt::from_manifest(&manifest).to_json().unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_mod_txt_full() {
+        let manifest = manifest(
+            r#"
+            [package]
+            name = "Full \"Quoted\" Mod"
+            version = "1.2.3"
+            description = "Line one\nLine \\two\\"
+            authors = ["A", "B"]
+
+            [superblt]
+            contact = "https://example.com"
+            color = "0.2 0.6 1"
+            image = "assets/icon.png"
+            blt_version = 2
+            priority = 50
+
+            [[superblt.updates]]
+            identifier = "full_mod"
+            host = "https://example.com/meta.json"
+
+            [[hooks]]
+            id = "lib/units/player"
+            entry = "src/low.lua"
+            output = "hooks/low.lua"
+
+            [[hooks]]
+            id = "lib/units/player"
+            entry = "src/high.lua"
+            output = "hooks/high.lua"
+            priority = 10
+
+            [[hooks]]
+            kind = "pre"
+            id = "lib/entry"
+            entry = "src/pre.lua"
+            output = "hooks/pre.lua"
+
+            [[hooks]]
+            kind = "persist"
+            global = "FullModLoaded"
+            entry = "src/persist.lua"
+            output = "hooks/persist.lua"
+
+            [[hooks]]
+            kind = "wildcard"
+            entry = "src/any.lua"
+            output = "hooks/any.lua"
+            "#,
+        );
+
+        let json = ModTxt::from_manifest(&manifest).to_json().unwrap();
+        assert_snapshot("full.mod.txt", &json);
+
+        let parsed: ModTxt = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, ModTxt::from_manifest(&manifest));
+    }
+}
@@ -0,0 +1,154 @@
+use super::{FileSystem, normalize};
+use pkg::ModPackage;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// In-memory filesystem. Clones share the same files, so a caller can keep a
+/// handle and read back what the compiler wrote.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFs {
+    files: Arc<Mutex<BTreeMap<PathBuf, Vec<u8>>>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount the files of a package under `root`
+    pub fn from_package(package: &ModPackage, root: &Path) -> Self {
+        let fs = Self::new();
+        for (path, content) in &package.files {
+            fs.insert(root.join(path), content.clone());
+        }
+        fs
+    }
+
+    pub fn insert(&self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) {
+        self.lock()
+            .insert(normalize(path.as_ref()), contents.into());
+    }
+
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.lock().get(&normalize(path.as_ref())).cloned()
+    }
+
+    /// All files currently stored, sorted by path
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, Vec<u8>>> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.get(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} not found in memory", path.display()),
+            )
+        })
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.insert(path, contents);
+        Ok(())
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.lock().contains_key(&normalize(path))
+    }
+
+    fn create_dir_all(&self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn canonicalize(&self, path: &Path) -> PathBuf {
+        normalize(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Compiler;
+    use pkg::OdrillProject;
+
+    #[test]
+    fn test_compile_package_in_memory() {
+        let mut package = ModPackage::new("demo", "1.0.0");
+        package.add_file(
+            "odrill.toml",
+            br#"
+            [package]
+            name = "demo"
+            version = "1.0.0"
+            authors = []
+
+            [[hooks]]
+            id = "lib/managers/menumanager"
+            entry = "src/main.lua"
+            output = "main.lua"
+
+            [[settings]]
+            id = "enabled"
+            type = "toggle"
+            title = "Enabled"
+            default = true
+            "#
+            .to_vec(),
+        );
+        package.add_file(
+            "src/main.lua",
+            b"use util::greet\nuse odrill::settings::get\nuse shared::shout\nlocal data = odrill.embed(\"data.json\")\ngreet(data.name)\n"
+                .to_vec(),
+        );
+        package.add_file(
+            "src/util.lua",
+            b"function greet(name)\n    log(name)\nend\n".to_vec(),
+        );
+        package.add_file("data.json", br#"{"name": "demo"}"#.to_vec());
+
+        let root = Path::new("/virtual/demo");
+        let project = OdrillProject::from_package(&package, root).unwrap();
+        let source = MemoryFs::from_package(&package, root);
+        // A local library standing in for target/pkg/shared
+        source.insert(
+            "/virtual/shared/src/init.lua",
+            b"function shout(text)\n    log(text)\nend\n".to_vec(),
+        );
+        let output = MemoryFs::new();
+
+        Compiler::new(project)
+            .with_source(source.clone())
+            .with_output(output.clone())
+            .with_package("shared", "/virtual/shared")
+            .compile_all()
+            .unwrap();
+
+        // The generated settings module goes to the output, not the sources
+        assert_eq!(
+            output.paths(),
+            vec![
+                root.join("dist/main.lua"),
+                root.join("target/odrill/settings.lua")
+            ]
+        );
+        assert!(
+            !source
+                .paths()
+                .iter()
+                .any(|p| p.starts_with(root.join("target")))
+        );
+        let bundle = String::from_utf8(output.get(root.join("dist/main.lua")).unwrap()).unwrap();
+        assert!(bundle.contains("function greet(name)"));
+        assert!(bundle.contains("function shout(text)"));
+        assert!(bundle.contains("local function get(key)"));
+        assert!(bundle.contains("name = \"demo\","));
+    }
+}
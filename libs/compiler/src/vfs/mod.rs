@@ -0,0 +1,54 @@
+//! Virtual filesystem used by the compiler for sources and outputs
+//!
+//! [`DiskFs`] works on the real filesystem, [`MemoryFs`] keeps everything in
+//! memory so a project (e.g. from a `ModPackage`) can be compiled without
+//! touching the disk. [`OverlayFs`] stacks the output on top of the sources.
+
+mod disk;
+mod memory;
+mod overlay;
+
+pub use disk::DiskFs;
+pub use memory::MemoryFs;
+pub use overlay::OverlayFs;
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+pub trait FileSystem: Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// Stable identity of a path, used to detect already processed files
+    fn canonicalize(&self, path: &Path) -> PathBuf;
+
+    /// Path on the real filesystem, if this file lives on disk
+    fn real_path(&self, _path: &Path) -> Option<PathBuf> {
+        None
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Resolve `.` and `..` components without touching the filesystem
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,79 @@
+use super::FileSystem;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Reads from `upper` then `lower`, writes to `upper` only. The compiler reads
+/// sources through it with the output on top, so generated modules and caches
+/// are written next to the bundles instead of into the sources.
+#[derive(Clone)]
+pub struct OverlayFs {
+    upper: Arc<dyn FileSystem>,
+    lower: Arc<dyn FileSystem>,
+}
+
+impl OverlayFs {
+    pub fn new(upper: Arc<dyn FileSystem>, lower: Arc<dyn FileSystem>) -> Self {
+        Self { upper, lower }
+    }
+
+    fn layer(&self, path: &Path) -> &dyn FileSystem {
+        if self.upper.is_file(path) {
+            self.upper.as_ref()
+        } else {
+            self.lower.as_ref()
+        }
+    }
+}
+
+impl FileSystem for OverlayFs {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.layer(path).read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.upper.write(path, contents)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.upper.is_file(path) || self.lower.is_file(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.upper.create_dir_all(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> PathBuf {
+        self.layer(path).canonicalize(path)
+    }
+
+    fn real_path(&self, path: &Path) -> Option<PathBuf> {
+        self.layer(path).real_path(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::MemoryFs;
+
+    #[test]
+    fn test_overlay() {
+        let upper = MemoryFs::new();
+        let lower = MemoryFs::new();
+        lower.insert("/p/src/a.lua", "lower");
+        let fs = OverlayFs::new(Arc::new(upper.clone()), Arc::new(lower.clone()));
+
+        fs.write(Path::new("/p/target/gen.lua"), b"gen").unwrap();
+        assert_eq!(
+            fs.read_to_string(Path::new("/p/src/a.lua")).unwrap(),
+            "lower"
+        );
+        assert_eq!(
+            fs.read_to_string(Path::new("/p/target/gen.lua")).unwrap(),
+            "gen"
+        );
+        assert!(upper.get("/p/target/gen.lua").is_some());
+        assert!(lower.get("/p/target/gen.lua").is_none());
+    }
+}
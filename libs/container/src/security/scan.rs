@@ -4,6 +4,12 @@ pub struct Scanner {
     suspicious_patterns: Vec<(Regex, &'static str)>,
 }
 
+impl Default for Scanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Scanner {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,90 @@
+//! Hashlists: the known strings behind idstrings, one per line
+
+use crate::Idstring;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Reverse lookup from idstrings to the strings they were hashed from
+#[derive(Debug, Clone, Default)]
+pub struct HashList {
+    strings: HashMap<Idstring, String>,
+}
+
+impl HashList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a hashlist. Blank lines are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut list = Self::new();
+        for line in content.lines() {
+            let line = line.trim_end_matches('\r');
+            if !line.is_empty() {
+                list.insert(line);
+            }
+        }
+        list
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read hashlist {}", path.display()))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Sorted strings, one per line
+    pub fn to_text(&self) -> String {
+        let mut strings: Vec<&str> = self.strings.values().map(String::as_str).collect();
+        strings.sort_unstable();
+
+        let mut text = strings.join("\n");
+        text.push('\n');
+        text
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_text())
+            .with_context(|| format!("Failed to write hashlist {}", path.display()))
+    }
+
+    pub fn insert(&mut self, s: &str) -> Idstring {
+        let id = Idstring::new(s);
+        self.strings.insert(id, s.to_string());
+        id
+    }
+
+    pub fn get(&self, id: Idstring) -> Option<&str> {
+        self.strings.get(&id).map(String::as_str)
+    }
+
+    pub fn contains(&self, s: &str) -> bool {
+        self.strings.contains_key(&Idstring::new(s))
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashlist_roundtrip() {
+        let list = HashList::parse("unit\r\ntexture\n\nguis/textures/pd2/hud_icons\n");
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(Idstring::new("texture")), Some("texture"));
+        assert!(list.contains("guis/textures/pd2/hud_icons"));
+        assert_eq!(
+            list.to_text(),
+            "guis/textures/pd2/hud_icons\ntexture\nunit\n"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Diesel `Idstring`: the 64-bit hash of asset paths, types and names
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Hash of a string as computed by Diesel (Bob Jenkins' lookup8, level 0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Idstring(pub u64);
+
+impl Idstring {
+    pub fn new(s: &str) -> Self {
+        Self(hash(s.as_bytes(), 0))
+    }
+}
+
+impl fmt::Display for Idstring {
+    /// Same as `Idstring:key()` in Lua
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for Idstring {
+    type Err = anyhow::Error;
+
+    /// Parse `5368e150b05a5b8c`, `0x5368e150b05a5b8c` or `@ID5368e150b05a5b8c@`
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        let hex = s
+            .strip_prefix("@ID")
+            .and_then(|h| h.strip_suffix('@'))
+            .or_else(|| s.strip_prefix("0x"))
+            .unwrap_or(s);
+
+        if hex.is_empty() || hex.len() > 16 {
+            anyhow::bail!("Invalid idstring '{}': expected up to 16 hex digits", s);
+        }
+        u64::from_str_radix(hex, 16)
+            .map(Idstring)
+            .map_err(|_| anyhow::anyhow!("Invalid idstring '{}': not hexadecimal", s))
+    }
+}
+
+/// lookup8 `hash()`
+pub fn hash(key: &[u8], level: u64) -> u64 {
+    let mut a = level;
+    let mut b = level;
+    let mut c = 0x9e37_79b9_7f4a_7c13u64;
+
+    let word = |bytes: &[u8]| u64::from_le_bytes(bytes.try_into().unwrap_or_default());
+
+    let mut chunks = key.chunks_exact(24);
+    for chunk in &mut chunks {
+        a = a.wrapping_add(word(&chunk[0..8]));
+        b = b.wrapping_add(word(&chunk[8..16]));
+        c = c.wrapping_add(word(&chunk[16..24]));
+        mix(&mut a, &mut b, &mut c);
+    }
+
+    // The low byte of c is reserved for the length
+    c = c.wrapping_add(key.len() as u64);
+    for (i, &byte) in chunks.remainder().iter().enumerate() {
+        let byte = byte as u64;
+        match i {
+            0..8 => a = a.wrapping_add(byte << (i * 8)),
+            8..16 => b = b.wrapping_add(byte << ((i - 8) * 8)),
+            _ => c = c.wrapping_add(byte << ((i - 15) * 8)),
+        }
+    }
+    mix(&mut a, &mut b, &mut c);
+
+    c
+}
+
+fn mix(a: &mut u64, b: &mut u64, c: &mut u64) {
+    macro_rules! step {
+        ($x:ident, $y:ident, $z:ident, $op:tt $shift:expr) => {
+            *$x = $x.wrapping_sub(*$y).wrapping_sub(*$z);
+            *$x ^= *$z $op $shift;
+        };
+    }
+
+    step!(a, b, c, >> 43);
+    step!(b, c, a, << 9);
+    step!(c, a, b, >> 8);
+    step!(a, b, c, >> 38);
+    step!(b, c, a, << 23);
+    step!(c, a, b, >> 5);
+    step!(a, b, c, >> 35);
+    step!(b, c, a, << 49);
+    step!(c, a, b, >> 11);
+    step!(a, b, c, >> 12);
+    step!(b, c, a, << 18);
+    step!(c, a, b, >> 22);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idstring() {
+        assert_eq!(Idstring::new("texture").to_string(), "5368e150b05a5b8c");
+        assert_eq!(
+            "@ID5368e150b05a5b8c@".parse::<Idstring>().unwrap(),
+            Idstring::new("texture")
+        );
+        assert!("not hex".parse::<Idstring>().is_err());
+    }
+}
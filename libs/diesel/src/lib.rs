@@ -0,0 +1,7 @@
+//! Diesel engine (PAYDAY 2) asset formats
+
+pub mod hashlist;
+pub mod idstring;
+
+pub use hashlist::HashList;
+pub use idstring::Idstring;
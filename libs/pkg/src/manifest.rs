@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -12,15 +12,159 @@ pub struct OdrillManifest {
     #[serde(default)]
     pub dependencies: HashMap<String, String>,
     #[serde(default)]
-    pub assets: Vec<PathBuf>,
+    pub assets: Vec<AssetConfig>,
     #[serde(default)]
     pub localization: Vec<LocalizationConfig>,
+    #[serde(default)]
+    pub preprocessors: Vec<PreprocessorConfig>,
+    #[serde(default)]
+    pub superblt: SuperBltConfig,
+    /// Present for mods built on BeardLib; enables main.xml generation
+    pub beardlib: Option<BeardLibConfig>,
+    #[serde(default)]
+    pub build: BuildConfig,
+    #[serde(default)]
+    pub settings: Vec<SettingConfig>,
+    #[serde(default)]
+    pub keybinds: Vec<KeybindConfig>,
+    /// `[overrides]`: mod_overrides folder name → source directory
+    #[serde(default)]
+    pub overrides: BTreeMap<String, PathBuf>,
+    #[serde(default)]
+    pub textures: Vec<TextureConfig>,
+    #[serde(default)]
+    pub run: RunConfig,
+}
+
+impl OdrillManifest {
+    /// Output target: `[build] target`, else inferred from `[package] kind`
+    /// and the presence of `[beardlib]`
+    pub fn target(&self) -> TargetKind {
+        self.build.target.unwrap_or(match self.package.kind {
+            PackageKind::Library => TargetKind::Library,
+            PackageKind::Mod if self.beardlib.is_some() => TargetKind::BeardLib,
+            PackageKind::Mod => TargetKind::SuperBlt,
+        })
+    }
+}
+
+/// `[[settings]]`: a mod option shown in the BLT options menu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingConfig {
+    /// Key used with `settings::get` and in the save file
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: SettingKind,
+    /// Menu label (English; translations use the generated loc keys)
+    pub title: String,
+    pub description: Option<String>,
+    /// bool for toggles, number for sliders, 1-based index for choices, key name for keybinds
+    pub default: Option<toml::Value>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub step: Option<f64>,
+    /// Labels of a choice setting
+    #[serde(default)]
+    pub choices: Vec<String>,
+    /// Base of the loc keys (`<loc_key>_title`, ...); defaults to `<package>_<id>`
+    pub loc_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingKind {
+    Toggle,
+    Slider,
+    Choice,
+    Keybind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BuildConfig {
+    pub target: Option<TargetKind>,
+}
+
+/// `[run]`: dev session settings for `odrill run`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RunConfig {
+    /// Installed mods kept during isolation (folder names or glob patterns)
+    #[serde(default)]
+    pub keep: Vec<String>,
+    /// Global config profile used when `--profile` isn't given
+    pub profile: Option<String>,
+    /// Other local projects built and installed in the same session,
+    /// relative to the project root
+    #[serde(default)]
+    pub with: Vec<PathBuf>,
+}
+
+/// What `odrill build` produces in dist/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetKind {
+    /// mod.txt based SuperBLT mod
+    SuperBlt,
+    /// SuperBLT mod with a BeardLib main.xml
+    BeardLib,
+    /// Package used through `use`, without mod files
+    Library,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    #[default]
+    Mod,
+    Library,
+}
+
+/// `[superblt]`: extra metadata written to mod.txt
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SuperBltConfig {
+    pub contact: Option<String>,
+    /// Mod colour in the BLT mods menu, as "r g b" (0-1)
+    pub color: Option<String>,
+    /// Icon shown in the BLT mods menu, relative to the project root
+    pub image: Option<PathBuf>,
+    pub blt_version: Option<u32>,
+    /// Load order of the mod; higher loads first
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub updates: Vec<UpdateConfig>,
+}
+
+/// `[[superblt.updates]]`: automatic update source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    pub identifier: String,
+    /// URL of the meta.json for self-hosted updates
+    pub host: Option<String>,
+    pub display_name: Option<String>,
+    pub install_dir: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalizationConfig {
     pub directory: String,
+    /// File used when the game language has no translation
     pub default: String,
+    /// Language name or code -> file in `directory`. When empty, languages
+    /// are discovered from the file names in `directory`.
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+}
+
+/// External command turning non-Lua sources (Teal, Fennel, ...) into Lua
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreprocessorConfig {
+    /// File extensions handled, without the dot (e.g. "fnl")
+    pub extensions: Vec<String>,
+    /// Command printing the generated Lua on stdout
+    pub command: String,
+    /// `{input}` is replaced by the source path; without it the source is piped to stdin
+    #[serde(default)]
+    pub args: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -29,25 +173,214 @@ pub struct PackageConfig {
     pub version: String,
     pub description: Option<String>,
     pub authors: Vec<String>,
+    #[serde(default)]
+    pub kind: PackageKind,
+}
+
+/// `[beardlib]`: modules written to main.xml
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BeardLibConfig {
+    pub priority: Option<i32>,
+    /// Global variable BeardLib stores the mod in
+    pub global_key: Option<String>,
+    /// Directory (in dist) holding the files of `add_files`
+    #[serde(default = "default_add_files_directory")]
+    pub add_files_directory: String,
+    #[serde(default)]
+    pub add_files: Vec<AddFileConfig>,
+    pub options: Option<BeardLibOptionsConfig>,
+    pub asset_updates: Option<AssetUpdatesConfig>,
+}
+
+fn default_add_files_directory() -> String {
+    "assets".to_string()
+}
+
+/// `[[beardlib.add_files]]`: asset loaded into the game database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddFileConfig {
+    /// Diesel file type, also the file extension (e.g. "texture", "unit")
+    #[serde(rename = "type")]
+    pub file_type: String,
+    /// Database path without extension (e.g. "guis/textures/my_icon")
+    pub path: String,
+}
+
+/// `[beardlib.options]`: options saved by BeardLib
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeardLibOptionsConfig {
+    pub save_file: Option<String>,
+    #[serde(default = "default_true")]
+    pub build_menu: bool,
+    #[serde(default)]
+    pub options: Vec<BeardLibOptionConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeardLibOptionConfig {
+    pub name: String,
+    /// "bool", "number" or "string"
+    #[serde(rename = "type")]
+    pub option_type: String,
+    pub default: toml::Value,
+}
+
+/// `[beardlib.asset_updates]`: BeardLib auto-updates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetUpdatesConfig {
+    pub id: String,
+    #[serde(default = "default_update_provider")]
+    pub provider: String,
+}
+
+fn default_update_provider() -> String {
+    "modworkshop".to_string()
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookConfig {
+    #[serde(default)]
+    pub kind: HookKind,
+    /// Game script hooked (e.g. `lib/managers/menumanager`); unused by persist and wildcard hooks
+    #[serde(default)]
     pub id: String,
     pub entry: PathBuf,
     pub output: PathBuf,
     #[serde(default)]
     pub priority: i32,
+    /// Persist hooks: global variable checked by SuperBLT; the script runs every frame until it is set
+    pub global: Option<String>,
+}
+
+/// An `assets` entry: a plain path, or a `[[assets]]` table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AssetConfig {
+    /// Copied to the same path in dist/
+    Path(PathBuf),
+    Entry(AssetEntry),
+}
+
+impl AssetConfig {
+    pub fn entry(&self) -> AssetEntry {
+        match self {
+            AssetConfig::Path(path) => AssetEntry {
+                from: path.clone(),
+                to: None,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                strict: false,
+            },
+            AssetConfig::Entry(entry) => entry.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetEntry {
+    /// File or directory, relative to the project
+    pub from: PathBuf,
+    /// Destination in dist/; defaults to `from`
+    pub to: Option<PathBuf>,
+    /// Globs (relative to `from`) of the files to copy; all files when empty
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs of files to skip, e.g. `*.psd`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Fail the build when `from` is missing or matches no file
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// `[[textures]]`: PNG/TGA images converted to DDS `.texture` files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureConfig {
+    /// Image or directory of images, relative to the project
+    pub from: PathBuf,
+    /// Destination in dist/; defaults to `from` with a `.texture` extension
+    pub to: Option<PathBuf>,
+    #[serde(default)]
+    pub format: TextureFormat,
+    #[serde(default)]
+    pub mipmaps: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextureFormat {
+    /// BC1, 1-bit alpha
+    Dxt1,
+    /// BC3, full alpha
+    #[default]
+    Dxt5,
+    /// 32-bit BGRA
+    Uncompressed,
+}
+
+/// `[[keybinds]]`: a SuperBLT keybind running a compiled handler script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindConfig {
+    pub id: String,
+    /// Loc key of the name shown in the keybinds menu (plain text if `localized = false`)
+    pub name: String,
+    /// Loc key of the description
+    #[serde(default)]
+    pub description: String,
+    /// Key bound until the player picks one (e.g. `f5`)
+    pub key: Option<String>,
+    pub entry: PathBuf,
+    pub output: PathBuf,
+    #[serde(default)]
+    pub run_in_menu: bool,
+    #[serde(default = "default_true")]
+    pub run_in_game: bool,
+    #[serde(default = "default_true")]
+    pub localized: bool,
 }
 
-#[allow(dead_code)]
-fn default_hook_type() -> String {
-    "post".to_string()
+/// Which mod.txt section a hook is written to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    /// Runs after the game script is loaded (`hooks`)
+    #[default]
+    Post,
+    /// Runs before the game script is loaded (`pre_hooks`)
+    Pre,
+    /// Runs every frame (`persist_scripts`)
+    Persist,
+    /// Runs after every game script (`hooks` with id `*`)
+    Wildcard,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct OptionsConfig {
-    // defaults?
+    /// Remove Lua comments from bundled output
+    #[serde(default)]
+    pub strip_comments: bool,
+    /// How `odrill build` reports localization keys missing from the default language
+    #[serde(default)]
+    pub loc_keys: CheckLevel,
+    /// How `odrill build` reports hook ids missing from the hook catalogue and
+    /// hooked methods missing from the class index. Unset, hook ids are only
+    /// checked against a full catalogue from `odrill hooks refresh`.
+    #[serde(default)]
+    pub hook_ids: Option<CheckLevel>,
+}
+
+/// Whether a build check warns, fails the build, or is skipped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckLevel {
+    #[default]
+    Warn,
+    Error,
+    Off,
 }
 
 // Reverted
@@ -1,4 +1,5 @@
 use crate::manifest::OdrillManifest;
+use crate::package::ModPackage;
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 #[derive(Debug, Clone)]
@@ -20,6 +21,22 @@ impl OdrillProject {
 
         Ok(Self { root, manifest })
     }
+
+    /// Load a project from the `odrill.toml` inside a package, rooted at `root`
+    pub fn from_package(package: &ModPackage, root: impl AsRef<Path>) -> Result<Self> {
+        let content = package
+            .get_file("odrill.toml")
+            .context("Package has no odrill.toml")?;
+
+        let manifest: OdrillManifest =
+            toml::from_str(std::str::from_utf8(content).context("odrill.toml is not valid UTF-8")?)
+                .context("Failed to parse odrill.toml")?;
+
+        Ok(Self {
+            root: root.as_ref().to_path_buf(),
+            manifest,
+        })
+    }
 }
 
 // Reverted
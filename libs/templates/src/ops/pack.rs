@@ -42,13 +42,13 @@ pub fn pack(path: impl AsRef<Path>) -> Result<Vec<u8>> {
         }
         let path = entry.path();
 
-        if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-            if ext == "lua" {
-                let content = fs::read_to_string(path).unwrap_or_default();
-                let issues = scanner.scan(&content);
-                if !issues.is_empty() {
-                    vulnerabilities.push((path.display().to_string(), issues));
-                }
+        if let Some(ext) = path.extension().and_then(|s| s.to_str())
+            && ext == "lua"
+        {
+            let content = fs::read_to_string(path).unwrap_or_default();
+            let issues = scanner.scan(&content);
+            if !issues.is_empty() {
+                vulnerabilities.push((path.display().to_string(), issues));
             }
         }
     }
@@ -63,7 +63,9 @@ pub fn pack(path: impl AsRef<Path>) -> Result<Vec<u8>> {
 
     // 4. Verify Compilation (Compilability)
     println!("  - Verifying compilation pass...");
-    let mut compiler = compiler::Compiler::new(odrill_project.clone());
+    // Bundles are written to memory so the template directory is left untouched
+    let mut compiler =
+        compiler::Compiler::new(odrill_project.clone()).with_output(compiler::vfs::MemoryFs::new());
     // We try to compile. If it fails (e.g. invalid hook definitions), we abort.
     match compiler.compile_all() {
         Ok(_) => println!("    {} Compilation successful.", "✓".green()),
@@ -75,9 +77,6 @@ pub fn pack(path: impl AsRef<Path>) -> Result<Vec<u8>> {
         }
     }
 
-    // Clean up dist/ if generated by verify
-    let _ = fs::remove_dir_all(root.join("dist"));
-
     // 5. Pack
     println!("📦 Packing template files...");
     let mut pkg = ModPackage::new(